@@ -0,0 +1,57 @@
+use crate::signals::{
+    DefaultSinkChanged, DefaultSourceChanged, InputStreamAdded, InputStreamChanged,
+    InputStreamRemoved, OutputStreamAdded, OutputStreamChanged, OutputStreamRemoved, SinkAdded,
+    SinkChanged, SinkRemoved, SourceAdded, SourceChanged, SourceRemoved,
+};
+
+use super::audio_structures::{
+    Card, ChannelVolumes, InputStream, LatencyOffset, OutputStream, ServerInfo, Sink, Source,
+};
+
+#[derive(Debug)]
+pub enum AudioEvent {
+    SinkAdded(SinkAdded),
+    SinkChanged(SinkChanged),
+    SinkRemoved(SinkRemoved),
+    DefaultSinkChanged(DefaultSinkChanged),
+    SourceAdded(SourceAdded),
+    SourceChanged(SourceChanged),
+    SourceRemoved(SourceRemoved),
+    DefaultSourceChanged(DefaultSourceChanged),
+    InputStreamAdded(InputStreamAdded),
+    InputStreamChanged(InputStreamChanged),
+    InputStreamRemoved(InputStreamRemoved),
+    OutputStreamAdded(OutputStreamAdded),
+    OutputStreamChanged(OutputStreamChanged),
+    OutputStreamRemoved(OutputStreamRemoved),
+}
+
+/// Mutations return false when the object does not exist or the server rejected the request.
+pub trait AudioBackend {
+    fn server_info(&mut self) -> Option<ServerInfo>;
+    fn sinks(&mut self) -> Vec<Sink>;
+    fn sources(&mut self) -> Vec<Source>;
+    fn cards(&mut self) -> Vec<Card>;
+    fn input_streams(&mut self) -> Vec<InputStream>;
+    fn output_streams(&mut self) -> Vec<OutputStream>;
+
+    fn subscribe(&mut self) -> bool;
+    /// Returns all events collected since the last call in the order they happened.
+    /// Bursts of changed events for the same object are merged into a single event.
+    fn take_events(&mut self) -> Vec<AudioEvent>;
+
+    fn set_sink_volume(&mut self, index: u32, volume: &ChannelVolumes) -> bool;
+    fn set_sink_mute(&mut self, index: u32, muted: bool) -> bool;
+    fn set_default_sink(&mut self, name: &str) -> bool;
+    fn set_source_volume(&mut self, index: u32, volume: &ChannelVolumes) -> bool;
+    fn set_source_mute(&mut self, index: u32, muted: bool) -> bool;
+    fn set_default_source(&mut self, name: &str) -> bool;
+    fn set_card_profile(&mut self, index: u32, profile: &str) -> bool;
+    fn set_port_latency_offset(&mut self, offset: &LatencyOffset) -> bool;
+    fn set_input_stream_volume(&mut self, index: u32, volume: &ChannelVolumes) -> bool;
+    fn set_input_stream_mute(&mut self, index: u32, muted: bool) -> bool;
+    fn move_input_stream(&mut self, index: u32, sink_index: u32) -> bool;
+    fn set_output_stream_volume(&mut self, index: u32, volume: &ChannelVolumes) -> bool;
+    fn set_output_stream_mute(&mut self, index: u32, muted: bool) -> bool;
+    fn move_output_stream(&mut self, index: u32, source_index: u32) -> bool;
+}
@@ -0,0 +1,380 @@
+use std::{
+    ops::{BitOr, BitOrAssign},
+    time::{Duration, Instant},
+};
+
+use dbus::{
+    arg::{self, Append, Arg, ArgType, Get},
+    Signature,
+};
+
+use super::audio_backend::AudioEvent;
+use super::audio_graph::NodeKind;
+use super::audio_structures::{InputStream, OutputStream, Sink, Source};
+
+/// Fields that changed in a `*Changed` signal, sent over D-Bus as `u`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChangeMask(pub u32);
+
+impl ChangeMask {
+    pub const NONE: ChangeMask = ChangeMask(0);
+    /// Name or alias of a device, name or application name of a stream.
+    pub const NAME: ChangeMask = ChangeMask(1);
+    pub const VOLUME: ChangeMask = ChangeMask(1 << 1);
+    pub const MUTE: ChangeMask = ChangeMask(1 << 2);
+    /// Active state of a device, corked state of a stream.
+    pub const STATE: ChangeMask = ChangeMask(1 << 3);
+    pub const PORT: ChangeMask = ChangeMask(1 << 4);
+    /// Sink or source of a stream.
+    pub const DEVICE: ChangeMask = ChangeMask(1 << 5);
+    /// Channels, channel map or sample spec.
+    pub const FORMAT: ChangeMask = ChangeMask(1 << 6);
+    pub const METADATA: ChangeMask = ChangeMask(1 << 7);
+    /// Used when the previous state is unknown.
+    pub const ALL: ChangeMask = ChangeMask(u32::MAX);
+
+    pub fn contains(&self, other: ChangeMask) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn intersects(&self, other: ChangeMask) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    fn set_if(&mut self, changed: bool, field: ChangeMask) {
+        if changed {
+            *self |= field;
+        }
+    }
+}
+
+impl BitOr for ChangeMask {
+    type Output = ChangeMask;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        ChangeMask(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for ChangeMask {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl Append for ChangeMask {
+    fn append_by_ref(&self, iter: &mut arg::IterAppend) {
+        iter.append(self.0);
+    }
+}
+
+impl<'a> Get<'a> for ChangeMask {
+    fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
+        Some(ChangeMask(u32::get(i)?))
+    }
+}
+
+impl Arg for ChangeMask {
+    const ARG_TYPE: arg::ArgType = ArgType::UInt32;
+    fn signature() -> Signature<'static> {
+        unsafe { Signature::from_slice_unchecked("u\0") }
+    }
+}
+
+impl Sink {
+    pub fn changes(&self, previous: &Sink) -> ChangeMask {
+        let mut mask = ChangeMask::NONE;
+        mask.set_if(
+            self.name != previous.name || self.alias != previous.alias,
+            ChangeMask::NAME,
+        );
+        mask.set_if(self.volume != previous.volume, ChangeMask::VOLUME);
+        mask.set_if(self.muted != previous.muted, ChangeMask::MUTE);
+        mask.set_if(self.active != previous.active, ChangeMask::STATE);
+        mask.set_if(
+            self.ports != previous.ports || self.active_port != previous.active_port,
+            ChangeMask::PORT,
+        );
+        mask.set_if(
+            self.channels != previous.channels
+                || self.channel_map != previous.channel_map
+                || self.sample_spec != previous.sample_spec,
+            ChangeMask::FORMAT,
+        );
+        mask.set_if(self.metadata != previous.metadata, ChangeMask::METADATA);
+        mask
+    }
+}
+
+impl Source {
+    pub fn changes(&self, previous: &Source) -> ChangeMask {
+        let mut mask = ChangeMask::NONE;
+        mask.set_if(
+            self.name != previous.name || self.alias != previous.alias,
+            ChangeMask::NAME,
+        );
+        mask.set_if(self.volume != previous.volume, ChangeMask::VOLUME);
+        mask.set_if(self.muted != previous.muted, ChangeMask::MUTE);
+        mask.set_if(self.active != previous.active, ChangeMask::STATE);
+        mask.set_if(
+            self.ports != previous.ports || self.active_port != previous.active_port,
+            ChangeMask::PORT,
+        );
+        mask.set_if(
+            self.channels != previous.channels
+                || self.channel_map != previous.channel_map
+                || self.sample_spec != previous.sample_spec,
+            ChangeMask::FORMAT,
+        );
+        mask.set_if(self.metadata != previous.metadata, ChangeMask::METADATA);
+        mask
+    }
+}
+
+impl InputStream {
+    pub fn changes(&self, previous: &InputStream) -> ChangeMask {
+        let mut mask = ChangeMask::NONE;
+        mask.set_if(
+            self.name != previous.name || self.application_name != previous.application_name,
+            ChangeMask::NAME,
+        );
+        mask.set_if(self.volume != previous.volume, ChangeMask::VOLUME);
+        mask.set_if(self.muted != previous.muted, ChangeMask::MUTE);
+        mask.set_if(self.corked != previous.corked, ChangeMask::STATE);
+        mask.set_if(self.sink_index != previous.sink_index, ChangeMask::DEVICE);
+        mask.set_if(
+            self.channels != previous.channels
+                || self.channel_map != previous.channel_map
+                || self.sample_spec != previous.sample_spec,
+            ChangeMask::FORMAT,
+        );
+        mask.set_if(self.metadata != previous.metadata, ChangeMask::METADATA);
+        mask
+    }
+}
+
+impl OutputStream {
+    pub fn changes(&self, previous: &OutputStream) -> ChangeMask {
+        let mut mask = ChangeMask::NONE;
+        mask.set_if(
+            self.name != previous.name || self.application_name != previous.application_name,
+            ChangeMask::NAME,
+        );
+        mask.set_if(self.volume != previous.volume, ChangeMask::VOLUME);
+        mask.set_if(self.muted != previous.muted, ChangeMask::MUTE);
+        mask.set_if(self.corked != previous.corked, ChangeMask::STATE);
+        mask.set_if(
+            self.source_index != previous.source_index,
+            ChangeMask::DEVICE,
+        );
+        mask.set_if(
+            self.channels != previous.channels
+                || self.channel_map != previous.channel_map
+                || self.sample_spec != previous.sample_spec,
+            ChangeMask::FORMAT,
+        );
+        mask.set_if(self.metadata != previous.metadata, ChangeMask::METADATA);
+        mask
+    }
+}
+
+/// Default time a `*Changed` event is held back to merge it with following changes.
+pub const DEFAULT_COALESCE_WINDOW: Duration = Duration::from_millis(50);
+
+/// Merges bursts of `*Changed` events for the same object into a single event with the latest
+/// state and the combined [`ChangeMask`].
+/// Any other event releases all pending changes before it to keep the order of events,
+/// a removal drops the pending changes of the removed object.
+#[derive(Debug)]
+pub struct ChangeCoalescer {
+    window: Duration,
+    pending: Vec<(Instant, AudioEvent)>,
+    ready: Vec<AudioEvent>,
+}
+
+impl Default for ChangeCoalescer {
+    fn default() -> Self {
+        Self::new(DEFAULT_COALESCE_WINDOW)
+    }
+}
+
+impl ChangeCoalescer {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            pending: Vec::new(),
+            ready: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, event: AudioEvent) {
+        self.push_at(event, Instant::now());
+    }
+
+    pub fn push_at(&mut self, event: AudioEvent, now: Instant) {
+        let Some(key) = changed_key(&event) else {
+            let removed = removed_key(&event);
+            let pending = std::mem::take(&mut self.pending);
+            self.ready.extend(
+                pending
+                    .into_iter()
+                    .map(|(_, pending)| pending)
+                    .filter(|pending| changed_key(pending) != removed),
+            );
+            self.ready.push(event);
+            return;
+        };
+        let pending = self
+            .pending
+            .iter_mut()
+            .find(|(_, pending)| changed_key(pending) == Some(key));
+        match pending {
+            Some((_, pending)) => merge(pending, event),
+            None => self.pending.push((now, event)),
+        }
+    }
+
+    /// Events whose window has passed, in the order they were first pushed.
+    pub fn take(&mut self) -> Vec<AudioEvent> {
+        self.take_at(Instant::now())
+    }
+
+    pub fn take_at(&mut self, now: Instant) -> Vec<AudioEvent> {
+        let window = self.window;
+        let (ready, pending): (Vec<_>, Vec<_>) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition(|(time, _)| now.duration_since(*time) >= window);
+        self.pending = pending;
+        self.ready.extend(ready.into_iter().map(|(_, event)| event));
+        std::mem::take(&mut self.ready)
+    }
+
+    /// Returns all events including the ones still inside their window.
+    pub fn flush(&mut self) -> Vec<AudioEvent> {
+        let pending = std::mem::take(&mut self.pending);
+        self.ready
+            .extend(pending.into_iter().map(|(_, event)| event));
+        std::mem::take(&mut self.ready)
+    }
+}
+
+fn changed_key(event: &AudioEvent) -> Option<(NodeKind, u32)> {
+    match event {
+        AudioEvent::SinkChanged(event) => Some((NodeKind::Sink, event.sink.index)),
+        AudioEvent::SourceChanged(event) => Some((NodeKind::Source, event.source.index)),
+        AudioEvent::InputStreamChanged(event) => Some((NodeKind::InputStream, event.stream.index)),
+        AudioEvent::OutputStreamChanged(event) => {
+            Some((NodeKind::OutputStream, event.stream.index))
+        }
+        _ => None,
+    }
+}
+
+fn removed_key(event: &AudioEvent) -> Option<(NodeKind, u32)> {
+    match event {
+        AudioEvent::SinkRemoved(event) => Some((NodeKind::Sink, event.index)),
+        AudioEvent::SourceRemoved(event) => Some((NodeKind::Source, event.index)),
+        AudioEvent::InputStreamRemoved(event) => Some((NodeKind::InputStream, event.index)),
+        AudioEvent::OutputStreamRemoved(event) => Some((NodeKind::OutputStream, event.index)),
+        _ => None,
+    }
+}
+
+fn merge(pending: &mut AudioEvent, event: AudioEvent) {
+    match (pending, event) {
+        (AudioEvent::SinkChanged(pending), AudioEvent::SinkChanged(event)) => {
+            pending.sink = event.sink;
+            pending.changes |= event.changes;
+        }
+        (AudioEvent::SourceChanged(pending), AudioEvent::SourceChanged(event)) => {
+            pending.source = event.source;
+            pending.changes |= event.changes;
+        }
+        (AudioEvent::InputStreamChanged(pending), AudioEvent::InputStreamChanged(event)) => {
+            pending.stream = event.stream;
+            pending.changes |= event.changes;
+        }
+        (AudioEvent::OutputStreamChanged(pending), AudioEvent::OutputStreamChanged(event)) => {
+            pending.stream = event.stream;
+            pending.changes |= event.changes;
+        }
+        _ => (),
+    }
+}
+
+#[test]
+fn change_mask_test() {
+    use super::audio_structures::ChannelVolumes;
+
+    let previous = Sink {
+        volume: ChannelVolumes::from_raw(&[65536, 65536]),
+        ..Default::default()
+    };
+    let mut sink = previous.clone();
+    assert!(sink.changes(&previous).is_empty());
+    sink.volume = ChannelVolumes::from_raw(&[32768, 32768]);
+    sink.muted = true;
+    let mask = sink.changes(&previous);
+    assert_eq!(mask, ChangeMask::VOLUME | ChangeMask::MUTE);
+    assert!(mask.contains(ChangeMask::VOLUME));
+    assert!(!mask.intersects(ChangeMask::NAME | ChangeMask::PORT));
+
+    let previous = InputStream::default();
+    let stream = InputStream {
+        sink_index: 3,
+        ..Default::default()
+    };
+    assert_eq!(stream.changes(&previous), ChangeMask::DEVICE);
+}
+
+#[test]
+fn change_coalescer_test() {
+    use super::audio_structures::ChannelVolumes;
+    use crate::signals::{SinkChanged, SinkRemoved, SourceChanged};
+
+    let start = Instant::now();
+    let window = Duration::from_millis(50);
+    let mut coalescer = ChangeCoalescer::new(window);
+    let changed = |volume: u32, changes: ChangeMask| {
+        AudioEvent::SinkChanged(SinkChanged {
+            sink: Sink {
+                index: 1,
+                volume: ChannelVolumes::from_raw(&[volume]),
+                ..Default::default()
+            },
+            changes,
+        })
+    };
+    coalescer.push_at(changed(100, ChangeMask::VOLUME), start);
+    coalescer.push_at(changed(200, ChangeMask::MUTE), start + window / 2);
+    coalescer.push_at(
+        AudioEvent::SourceChanged(SourceChanged {
+            source: Source::default(),
+            changes: ChangeMask::NAME,
+        }),
+        start + window / 2,
+    );
+    assert!(coalescer.take_at(start + window / 4).is_empty());
+
+    let events = coalescer.take_at(start + window);
+    assert_eq!(events.len(), 1);
+    let AudioEvent::SinkChanged(event) = &events[0] else {
+        panic!("expected SinkChanged");
+    };
+    assert_eq!(event.sink.volume.to_raw(), vec![200]);
+    assert_eq!(event.changes, ChangeMask::VOLUME | ChangeMask::MUTE);
+
+    coalescer.push_at(changed(300, ChangeMask::VOLUME), start + window);
+    coalescer.push_at(
+        AudioEvent::SinkRemoved(SinkRemoved { index: 1 }),
+        start + window,
+    );
+    let events = coalescer.flush();
+    assert_eq!(events.len(), 2);
+    assert!(matches!(events[0], AudioEvent::SourceChanged(_)));
+    assert!(matches!(events[1], AudioEvent::SinkRemoved(_)));
+}
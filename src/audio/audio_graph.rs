@@ -0,0 +1,374 @@
+use dbus::{
+    arg::{self, Append, Arg, ArgType, Get},
+    Signature,
+};
+
+use crate::network::connection::Enum;
+
+use super::audio_structures::{
+    InputStream, OutputStream, Sink, Source, TAudioObject, TAudioStreamObject,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum NodeKind {
+    #[default]
+    Sink,
+    Source,
+    InputStream,
+    OutputStream,
+}
+
+impl Enum for NodeKind {
+    fn from_i32(num: i32) -> Self {
+        match num {
+            1 => NodeKind::Source,
+            2 => NodeKind::InputStream,
+            3 => NodeKind::OutputStream,
+            _ => NodeKind::Sink,
+        }
+    }
+
+    fn to_i32(&self) -> i32 {
+        match self {
+            NodeKind::Sink => 0,
+            NodeKind::Source => 1,
+            NodeKind::InputStream => 2,
+            NodeKind::OutputStream => 3,
+        }
+    }
+}
+
+impl Append for NodeKind {
+    fn append_by_ref(&self, iter: &mut arg::IterAppend) {
+        iter.append(self.to_i32());
+    }
+}
+
+impl<'a> Get<'a> for NodeKind {
+    fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
+        Some(NodeKind::from_i32(i32::get(i)?))
+    }
+}
+
+impl Arg for NodeKind {
+    const ARG_TYPE: arg::ArgType = ArgType::Int32;
+    fn signature() -> Signature<'static> {
+        unsafe { Signature::from_slice_unchecked("i\0") }
+    }
+}
+
+/// Device or stream in the [`AudioGraph`], the index is only unique per kind.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Node {
+    pub kind: NodeKind,
+    pub index: u32,
+    /// Alias for devices, application name for streams.
+    pub name: String,
+}
+
+impl Append for Node {
+    fn append_by_ref(&self, iter: &mut arg::IterAppend) {
+        iter.append_struct(|i| {
+            i.append(self.kind);
+            i.append(self.index);
+            i.append(&self.name);
+        });
+    }
+}
+
+impl<'a> Get<'a> for Node {
+    fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
+        let (kind, index, name) = <(NodeKind, u32, String)>::get(i)?;
+        Some(Self { kind, index, name })
+    }
+}
+
+impl Arg for Node {
+    const ARG_TYPE: arg::ArgType = ArgType::Struct;
+    fn signature() -> Signature<'static> {
+        unsafe { Signature::from_slice_unchecked("(ius)\0") }
+    }
+}
+
+/// Audio flowing from one node to another:
+/// input stream to sink, sink to its monitor source and source to output stream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Edge {
+    pub from_kind: NodeKind,
+    pub from_index: u32,
+    pub to_kind: NodeKind,
+    pub to_index: u32,
+}
+
+impl Edge {
+    pub fn new(from_kind: NodeKind, from_index: u32, to_kind: NodeKind, to_index: u32) -> Self {
+        Self {
+            from_kind,
+            from_index,
+            to_kind,
+            to_index,
+        }
+    }
+
+    pub fn starts_at(&self, kind: NodeKind, index: u32) -> bool {
+        self.from_kind == kind && self.from_index == index
+    }
+
+    pub fn ends_at(&self, kind: NodeKind, index: u32) -> bool {
+        self.to_kind == kind && self.to_index == index
+    }
+}
+
+impl Append for Edge {
+    fn append_by_ref(&self, iter: &mut arg::IterAppend) {
+        iter.append_struct(|i| {
+            i.append(self.from_kind);
+            i.append(self.from_index);
+            i.append(self.to_kind);
+            i.append(self.to_index);
+        });
+    }
+}
+
+impl<'a> Get<'a> for Edge {
+    fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
+        let (from_kind, from_index, to_kind, to_index) = <(NodeKind, u32, NodeKind, u32)>::get(i)?;
+        Some(Self {
+            from_kind,
+            from_index,
+            to_kind,
+            to_index,
+        })
+    }
+}
+
+impl Arg for Edge {
+    const ARG_TYPE: arg::ArgType = ArgType::Struct;
+    fn signature() -> Signature<'static> {
+        unsafe { Signature::from_slice_unchecked("(iuiu)\0") }
+    }
+}
+
+/// Streams whose device is unknown are kept as nodes without edges.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AudioGraph {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+impl AudioGraph {
+    pub fn new(
+        sinks: &[Sink],
+        sources: &[Source],
+        input_streams: &[InputStream],
+        output_streams: &[OutputStream],
+    ) -> Self {
+        let mut graph = AudioGraph::default();
+        for sink in sinks.iter() {
+            graph.push_node(NodeKind::Sink, sink.index(), sink.alias());
+        }
+        for source in sources.iter() {
+            graph.push_node(NodeKind::Source, source.index(), source.alias());
+            if source.is_monitor() {
+                if let Some(sink) = sinks
+                    .iter()
+                    .find(|sink| sink.name() == source.monitor_name())
+                {
+                    graph.edges.push(Edge::new(
+                        NodeKind::Sink,
+                        sink.index(),
+                        NodeKind::Source,
+                        source.index(),
+                    ));
+                }
+            }
+        }
+        for stream in input_streams.iter() {
+            graph.push_node(
+                NodeKind::InputStream,
+                stream.index(),
+                stream.application_name(),
+            );
+            if sinks
+                .iter()
+                .any(|sink| sink.index() == stream.audio_object_index())
+            {
+                graph.edges.push(Edge::new(
+                    NodeKind::InputStream,
+                    stream.index(),
+                    NodeKind::Sink,
+                    stream.audio_object_index(),
+                ));
+            }
+        }
+        for stream in output_streams.iter() {
+            graph.push_node(
+                NodeKind::OutputStream,
+                stream.index(),
+                stream.application_name(),
+            );
+            if sources
+                .iter()
+                .any(|source| source.index() == stream.audio_object_index())
+            {
+                graph.edges.push(Edge::new(
+                    NodeKind::Source,
+                    stream.audio_object_index(),
+                    NodeKind::OutputStream,
+                    stream.index(),
+                ));
+            }
+        }
+        graph
+    }
+
+    pub fn node(&self, kind: NodeKind, index: u32) -> Option<&Node> {
+        self.nodes
+            .iter()
+            .find(|node| node.kind == kind && node.index == index)
+    }
+
+    pub fn inputs(&self, kind: NodeKind, index: u32) -> Vec<&Node> {
+        self.edges
+            .iter()
+            .filter(|edge| edge.ends_at(kind, index))
+            .filter_map(|edge| self.node(edge.from_kind, edge.from_index))
+            .collect()
+    }
+
+    pub fn outputs(&self, kind: NodeKind, index: u32) -> Vec<&Node> {
+        self.edges
+            .iter()
+            .filter(|edge| edge.starts_at(kind, index))
+            .filter_map(|edge| self.node(edge.to_kind, edge.to_index))
+            .collect()
+    }
+
+    pub fn streams_on(&self, kind: NodeKind, index: u32) -> Vec<&Node> {
+        match kind {
+            NodeKind::Sink => self
+                .inputs(kind, index)
+                .into_iter()
+                .filter(|node| node.kind == NodeKind::InputStream)
+                .collect(),
+            NodeKind::Source => self
+                .outputs(kind, index)
+                .into_iter()
+                .filter(|node| node.kind == NodeKind::OutputStream)
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn device_of(&self, kind: NodeKind, index: u32) -> Option<&Node> {
+        match kind {
+            NodeKind::InputStream => self.outputs(kind, index).into_iter().next(),
+            NodeKind::OutputStream => self.inputs(kind, index).into_iter().next(),
+            _ => None,
+        }
+    }
+
+    pub fn monitor_of(&self, sink_index: u32) -> Option<&Node> {
+        self.outputs(NodeKind::Sink, sink_index)
+            .into_iter()
+            .find(|node| node.kind == NodeKind::Source)
+    }
+
+    fn push_node(&mut self, kind: NodeKind, index: u32, name: String) {
+        self.nodes.push(Node { kind, index, name });
+    }
+}
+
+impl Append for AudioGraph {
+    fn append_by_ref(&self, iter: &mut arg::IterAppend) {
+        iter.append_struct(|i| {
+            i.append(&self.nodes);
+            i.append(&self.edges);
+        });
+    }
+}
+
+impl<'a> Get<'a> for AudioGraph {
+    fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
+        let (nodes, edges) = <(Vec<Node>, Vec<Edge>)>::get(i)?;
+        Some(Self { nodes, edges })
+    }
+}
+
+impl Arg for AudioGraph {
+    const ARG_TYPE: arg::ArgType = ArgType::Struct;
+    fn signature() -> Signature<'static> {
+        unsafe { Signature::from_slice_unchecked("(a(ius)a(iuiu))\0") }
+    }
+}
+
+#[test]
+fn audio_graph_test() {
+    let sinks = vec![Sink {
+        index: 1,
+        name: String::from("speakers"),
+        alias: String::from("Speakers"),
+        ..Default::default()
+    }];
+    let mut monitor = Source {
+        index: 4,
+        alias: String::from("Monitor of Speakers"),
+        ..Default::default()
+    };
+    monitor.metadata.monitor_name = String::from("speakers");
+    let microphone = Source {
+        index: 5,
+        alias: String::from("Microphone"),
+        ..Default::default()
+    };
+    let input_streams = vec![
+        InputStream {
+            index: 10,
+            application_name: String::from("Firefox"),
+            sink_index: 1,
+            ..Default::default()
+        },
+        InputStream {
+            index: 11,
+            application_name: String::from("Orphan"),
+            sink_index: 99,
+            ..Default::default()
+        },
+    ];
+    let output_streams = vec![OutputStream {
+        index: 20,
+        application_name: String::from("OBS"),
+        source_index: 4,
+        ..Default::default()
+    }];
+    let graph = AudioGraph::new(
+        &sinks,
+        &[monitor, microphone],
+        &input_streams,
+        &output_streams,
+    );
+    assert_eq!(graph.nodes.len(), 6);
+    assert_eq!(graph.edges.len(), 3);
+
+    let streams = graph.streams_on(NodeKind::Sink, 1);
+    assert_eq!(streams.len(), 1);
+    assert_eq!(streams[0].name, "Firefox");
+    assert_eq!(graph.monitor_of(1).unwrap().index, 4);
+    assert_eq!(graph.streams_on(NodeKind::Source, 4)[0].name, "OBS");
+    assert!(graph.streams_on(NodeKind::Source, 5).is_empty());
+    assert!(graph.device_of(NodeKind::InputStream, 11).is_none());
+    assert_eq!(
+        graph.device_of(NodeKind::OutputStream, 20).unwrap().name,
+        "Monitor of Speakers"
+    );
+
+    let message = dbus::Message::new_signal("/test", "org.test", "Test")
+        .unwrap()
+        .append1(&graph);
+    assert_eq!(
+        message.iter_init().signature().to_string(),
+        "(a(ius)a(iuiu))"
+    );
+    let received: AudioGraph = message.read1().unwrap();
+    assert_eq!(received, graph);
+}
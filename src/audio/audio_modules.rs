@@ -0,0 +1,419 @@
+use std::fmt::Display;
+
+use super::audio_structures::ChannelPosition;
+
+pub trait ModuleBuilder {
+    fn module_name(&self) -> &'static str;
+    fn argument(&self) -> String;
+}
+
+/// Virtual sink that discards all audio, its monitor can be used as a source.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NullSink {
+    pub sink_name: String,
+    pub description: Option<String>,
+    pub rate: Option<u32>,
+    pub channel_map: Vec<ChannelPosition>,
+}
+
+impl NullSink {
+    pub fn new(sink_name: &str) -> Self {
+        Self {
+            sink_name: String::from(sink_name),
+            ..Default::default()
+        }
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(String::from(description));
+        self
+    }
+
+    pub fn rate(mut self, rate: u32) -> Self {
+        self.rate = Some(rate);
+        self
+    }
+
+    pub fn channel_map(mut self, channel_map: Vec<ChannelPosition>) -> Self {
+        self.channel_map = channel_map;
+        self
+    }
+}
+
+impl ModuleBuilder for NullSink {
+    fn module_name(&self) -> &'static str {
+        "module-null-sink"
+    }
+
+    fn argument(&self) -> String {
+        let mut arguments = ModuleArguments::default();
+        arguments.push("sink_name", &self.sink_name);
+        arguments.push_properties("sink_properties", &self.description);
+        arguments.push_option("rate", &self.rate);
+        arguments.push_channel_map("channel_map", &self.channel_map);
+        arguments.to_string()
+    }
+}
+
+/// Unset devices use the defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Loopback {
+    pub source: Option<String>,
+    pub sink: Option<String>,
+    pub latency_msec: Option<u32>,
+    /// Keeps the loopback on its devices instead of following the defaults.
+    pub dont_move: bool,
+}
+
+impl Loopback {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn source(mut self, source: &str) -> Self {
+        self.source = Some(String::from(source));
+        self
+    }
+
+    pub fn sink(mut self, sink: &str) -> Self {
+        self.sink = Some(String::from(sink));
+        self
+    }
+
+    pub fn latency_msec(mut self, latency_msec: u32) -> Self {
+        self.latency_msec = Some(latency_msec);
+        self
+    }
+
+    pub fn dont_move(mut self, dont_move: bool) -> Self {
+        self.dont_move = dont_move;
+        self
+    }
+}
+
+impl ModuleBuilder for Loopback {
+    fn module_name(&self) -> &'static str {
+        "module-loopback"
+    }
+
+    fn argument(&self) -> String {
+        let mut arguments = ModuleArguments::default();
+        arguments.push_option("source", &self.source);
+        arguments.push_option("sink", &self.sink);
+        arguments.push_option("latency_msec", &self.latency_msec);
+        if self.dont_move {
+            arguments.push("source_dont_move", "true");
+            arguments.push("sink_dont_move", "true");
+        }
+        arguments.to_string()
+    }
+}
+
+/// Without sinks all available sinks are used.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CombineSink {
+    pub sink_name: String,
+    pub description: Option<String>,
+    pub sinks: Vec<String>,
+}
+
+impl CombineSink {
+    pub fn new(sink_name: &str) -> Self {
+        Self {
+            sink_name: String::from(sink_name),
+            ..Default::default()
+        }
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(String::from(description));
+        self
+    }
+
+    pub fn sink(mut self, sink: &str) -> Self {
+        self.sinks.push(String::from(sink));
+        self
+    }
+}
+
+impl ModuleBuilder for CombineSink {
+    fn module_name(&self) -> &'static str {
+        "module-combine-sink"
+    }
+
+    fn argument(&self) -> String {
+        let mut arguments = ModuleArguments::default();
+        arguments.push("sink_name", &self.sink_name);
+        arguments.push_properties("sink_properties", &self.description);
+        if !self.sinks.is_empty() {
+            // "slaves" is still the only name understood by older pulse versions
+            arguments.push("slaves", &self.sinks.join(","));
+        }
+        arguments.to_string()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EchoCancelMethod {
+    #[default]
+    WebRtc,
+    Speex,
+    Null,
+}
+
+impl Display for EchoCancelMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EchoCancelMethod::WebRtc => f.write_str("webrtc"),
+            EchoCancelMethod::Speex => f.write_str("speex"),
+            EchoCancelMethod::Null => f.write_str("null"),
+        }
+    }
+}
+
+/// Creates a source with echo and noise cancellation for `source_master` together with the
+/// sink the cancelled audio is played on.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EchoCancel {
+    pub source_name: String,
+    pub sink_name: String,
+    pub source_master: Option<String>,
+    pub sink_master: Option<String>,
+    pub source_description: Option<String>,
+    pub sink_description: Option<String>,
+    pub method: EchoCancelMethod,
+    /// Raw algorithm options, e.g. "noise_suppression=true".
+    pub method_arguments: Option<String>,
+}
+
+impl EchoCancel {
+    pub fn new(source_name: &str, sink_name: &str) -> Self {
+        Self {
+            source_name: String::from(source_name),
+            sink_name: String::from(sink_name),
+            ..Default::default()
+        }
+    }
+
+    pub fn source_master(mut self, source_master: &str) -> Self {
+        self.source_master = Some(String::from(source_master));
+        self
+    }
+
+    pub fn sink_master(mut self, sink_master: &str) -> Self {
+        self.sink_master = Some(String::from(sink_master));
+        self
+    }
+
+    pub fn source_description(mut self, description: &str) -> Self {
+        self.source_description = Some(String::from(description));
+        self
+    }
+
+    pub fn sink_description(mut self, description: &str) -> Self {
+        self.sink_description = Some(String::from(description));
+        self
+    }
+
+    pub fn method(mut self, method: EchoCancelMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    pub fn method_arguments(mut self, method_arguments: &str) -> Self {
+        self.method_arguments = Some(String::from(method_arguments));
+        self
+    }
+}
+
+impl ModuleBuilder for EchoCancel {
+    fn module_name(&self) -> &'static str {
+        "module-echo-cancel"
+    }
+
+    fn argument(&self) -> String {
+        let mut arguments = ModuleArguments::default();
+        arguments.push("source_name", &self.source_name);
+        arguments.push("sink_name", &self.sink_name);
+        arguments.push_option("source_master", &self.source_master);
+        arguments.push_option("sink_master", &self.sink_master);
+        arguments.push_properties("source_properties", &self.source_description);
+        arguments.push_properties("sink_properties", &self.sink_description);
+        arguments.push("aec_method", &self.method.to_string());
+        arguments.push_option("aec_args", &self.method_arguments);
+        arguments.to_string()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RemapSink {
+    pub sink_name: String,
+    pub master: String,
+    pub description: Option<String>,
+    pub channel_map: Vec<ChannelPosition>,
+    pub master_channel_map: Vec<ChannelPosition>,
+    /// Lets pulse up- or downmix channels that do not match, defaults to true.
+    pub remix: Option<bool>,
+}
+
+impl RemapSink {
+    pub fn new(sink_name: &str, master: &str) -> Self {
+        Self {
+            sink_name: String::from(sink_name),
+            master: String::from(master),
+            ..Default::default()
+        }
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(String::from(description));
+        self
+    }
+
+    /// Channel `n` of the new sink is played on channel `n` of `master_channel_map`.
+    pub fn channel_maps(
+        mut self,
+        channel_map: Vec<ChannelPosition>,
+        master_channel_map: Vec<ChannelPosition>,
+    ) -> Self {
+        self.channel_map = channel_map;
+        self.master_channel_map = master_channel_map;
+        self
+    }
+
+    pub fn remix(mut self, remix: bool) -> Self {
+        self.remix = Some(remix);
+        self
+    }
+}
+
+impl ModuleBuilder for RemapSink {
+    fn module_name(&self) -> &'static str {
+        "module-remap-sink"
+    }
+
+    fn argument(&self) -> String {
+        let mut arguments = ModuleArguments::default();
+        arguments.push("sink_name", &self.sink_name);
+        arguments.push("master", &self.master);
+        arguments.push_properties("sink_properties", &self.description);
+        if !self.channel_map.is_empty() {
+            arguments.push("channels", &self.channel_map.len().to_string());
+        }
+        arguments.push_channel_map("channel_map", &self.channel_map);
+        arguments.push_channel_map("master_channel_map", &self.master_channel_map);
+        arguments.push_option("remix", &self.remix);
+        arguments.to_string()
+    }
+}
+
+#[derive(Default)]
+struct ModuleArguments {
+    arguments: Vec<String>,
+}
+
+impl ModuleArguments {
+    fn push(&mut self, key: &str, value: &str) {
+        self.arguments
+            .push(format!("{}={}", key, quote(value, '"')));
+    }
+
+    fn push_option<T: Display>(&mut self, key: &str, value: &Option<T>) {
+        if let Some(value) = value {
+            self.push(key, &value.to_string());
+        }
+    }
+
+    fn push_properties(&mut self, key: &str, description: &Option<String>) {
+        if let Some(description) = description {
+            let properties = format!("device.description={}", quote(description, '\''));
+            self.push(key, &properties);
+        }
+    }
+
+    fn push_channel_map(&mut self, key: &str, channel_map: &[ChannelPosition]) {
+        if !channel_map.is_empty() {
+            let positions: Vec<String> = channel_map
+                .iter()
+                .map(ChannelPosition::system_name)
+                .collect();
+            self.push(key, &positions.join(","));
+        }
+    }
+}
+
+impl Display for ModuleArguments {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.arguments.join(" "))
+    }
+}
+
+/// Pulse splits arguments at whitespace, values containing whitespace or quotes are wrapped
+/// in `quote` with backslash escapes.
+fn quote(value: &str, quote: char) -> String {
+    if !value.is_empty()
+        && !value
+            .chars()
+            .any(|c| c.is_whitespace() || c == '"' || c == '\'' || c == '\\')
+    {
+        return String::from(value);
+    }
+    let mut quoted = String::from(quote);
+    for c in value.chars() {
+        if c == '"' || c == '\'' || c == '\\' {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push(quote);
+    quoted
+}
+
+#[test]
+fn module_argument_test() {
+    let null_sink = NullSink::new("reset_null")
+        .description("Virtual Output")
+        .rate(48000)
+        .channel_map(vec![
+            ChannelPosition::FrontLeft,
+            ChannelPosition::FrontRight,
+        ]);
+    assert_eq!(null_sink.module_name(), "module-null-sink");
+    assert_eq!(
+        null_sink.argument(),
+        "sink_name=reset_null sink_properties=\"device.description=\\'Virtual Output\\'\" \
+         rate=48000 channel_map=front-left,front-right"
+    );
+
+    let combine = CombineSink::new("combined")
+        .sink("speakers")
+        .sink("headset");
+    assert_eq!(
+        combine.argument(),
+        "sink_name=combined slaves=speakers,headset"
+    );
+
+    let echo_cancel = EchoCancel::new("noise_cancelled", "echo_sink")
+        .source_master("microphone")
+        .method_arguments("noise_suppression=true");
+    assert_eq!(
+        echo_cancel.argument(),
+        "source_name=noise_cancelled sink_name=echo_sink source_master=microphone \
+         aec_method=webrtc aec_args=noise_suppression=true"
+    );
+
+    let loopback = Loopback::new().source("microphone").latency_msec(20);
+    assert_eq!(loopback.argument(), "source=microphone latency_msec=20");
+
+    let remap = RemapSink::new("swapped", "speakers")
+        .channel_maps(
+            vec![ChannelPosition::FrontLeft, ChannelPosition::FrontRight],
+            vec![ChannelPosition::FrontRight, ChannelPosition::FrontLeft],
+        )
+        .remix(false);
+    assert_eq!(
+        remap.argument(),
+        "sink_name=swapped master=speakers channels=2 channel_map=front-left,front-right \
+         master_channel_map=front-right,front-left remix=false"
+    );
+}
@@ -0,0 +1,634 @@
+use std::collections::BTreeMap;
+
+use toml::{Table, Value};
+
+use crate::utils::config::{parse_config, write_config_value};
+
+use super::audio_structures::{
+    Card, ChannelVolumes, InputStream, OutputStream, ServerInfo, Sink, Source,
+};
+
+pub const SNAPSHOT_CATEGORY: &str = "AudioSnapshots";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeviceSnapshot {
+    pub volume: ChannelVolumes,
+    pub muted: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StreamSnapshot {
+    /// Index of the stream when the snapshot was taken, not saved as it changes with every
+    /// new stream.
+    pub index: u32,
+    /// Used to tell apart the streams of an application, e.g. music and voice of a game.
+    pub media_role: String,
+    pub device: String,
+    pub volume: ChannelVolumes,
+    pub muted: bool,
+}
+
+/// Devices and cards are keyed by their stable pulse names and streams are grouped by their
+/// application name, as indices change whenever a device is reconnected.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AudioSnapshot {
+    pub default_sink: String,
+    pub default_source: String,
+    pub sinks: BTreeMap<String, DeviceSnapshot>,
+    pub sources: BTreeMap<String, DeviceSnapshot>,
+    /// Active profile per card.
+    pub card_profiles: BTreeMap<String, String>,
+    /// Streams per application, ordered by index.
+    pub input_streams: BTreeMap<String, Vec<StreamSnapshot>>,
+    pub output_streams: BTreeMap<String, Vec<StreamSnapshot>>,
+}
+
+/// Devices and cards are referenced by name, streams by their current index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AudioChange {
+    DefaultSink(String),
+    DefaultSource(String),
+    SinkVolume(String, ChannelVolumes),
+    SinkMute(String, bool),
+    SourceVolume(String, ChannelVolumes),
+    SourceMute(String, bool),
+    CardProfile(String, String),
+    InputStreamSink(u32, String),
+    InputStreamVolume(u32, ChannelVolumes),
+    InputStreamMute(u32, bool),
+    OutputStreamSource(u32, String),
+    OutputStreamVolume(u32, ChannelVolumes),
+    OutputStreamMute(u32, bool),
+}
+
+impl AudioSnapshot {
+    pub fn new(
+        server_info: &ServerInfo,
+        sinks: &[Sink],
+        sources: &[Source],
+        cards: &[Card],
+        input_streams: &[InputStream],
+        output_streams: &[OutputStream],
+    ) -> Self {
+        let sink_names: BTreeMap<u32, String> = sinks
+            .iter()
+            .map(|sink| (sink.index, sink.name.clone()))
+            .collect();
+        let source_names: BTreeMap<u32, String> = sources
+            .iter()
+            .map(|source| (source.index, source.name.clone()))
+            .collect();
+        Self {
+            default_sink: server_info.default_sink.clone(),
+            default_source: server_info.default_source.clone(),
+            sinks: sinks
+                .iter()
+                .map(|sink| {
+                    (
+                        sink.name.clone(),
+                        DeviceSnapshot::new(&sink.volume, sink.muted),
+                    )
+                })
+                .collect(),
+            sources: sources
+                .iter()
+                .map(|source| {
+                    (
+                        source.name.clone(),
+                        DeviceSnapshot::new(&source.volume, source.muted),
+                    )
+                })
+                .collect(),
+            card_profiles: cards
+                .iter()
+                .map(|card| (card.system_name.clone(), card.active_profile.clone()))
+                .collect(),
+            input_streams: group_streams(input_streams.iter().map(|stream| {
+                (
+                    stream.application_name.clone(),
+                    StreamSnapshot {
+                        index: stream.index,
+                        media_role: stream.metadata.media_role.clone(),
+                        device: sink_names
+                            .get(&stream.sink_index)
+                            .cloned()
+                            .unwrap_or_default(),
+                        volume: stream.volume.clone(),
+                        muted: stream.muted,
+                    },
+                )
+            })),
+            output_streams: group_streams(output_streams.iter().map(|stream| {
+                (
+                    stream.application_name.clone(),
+                    StreamSnapshot {
+                        index: stream.index,
+                        media_role: stream.metadata.media_role.clone(),
+                        device: source_names
+                            .get(&stream.source_index)
+                            .cloned()
+                            .unwrap_or_default(),
+                        volume: stream.volume.clone(),
+                        muted: stream.muted,
+                    },
+                )
+            })),
+        }
+    }
+
+    /// Returns the changes needed to get from `current` to this snapshot.
+    /// Entries that do not exist in `current`, e.g. unplugged devices, are skipped.
+    pub fn diff(&self, current: &AudioSnapshot) -> Vec<AudioChange> {
+        let mut changes = Vec::new();
+        if !self.default_sink.is_empty()
+            && self.default_sink != current.default_sink
+            && current.sinks.contains_key(&self.default_sink)
+        {
+            changes.push(AudioChange::DefaultSink(self.default_sink.clone()));
+        }
+        if !self.default_source.is_empty()
+            && self.default_source != current.default_source
+            && current.sources.contains_key(&self.default_source)
+        {
+            changes.push(AudioChange::DefaultSource(self.default_source.clone()));
+        }
+        for (card, profile) in self.card_profiles.iter() {
+            if let Some(current_profile) = current.card_profiles.get(card) {
+                if current_profile != profile {
+                    changes.push(AudioChange::CardProfile(card.clone(), profile.clone()));
+                }
+            }
+        }
+        for (name, target) in self.sinks.iter() {
+            if let Some(device) = current.sinks.get(name) {
+                if let Some(volume) = volume_change(&target.volume, &device.volume) {
+                    changes.push(AudioChange::SinkVolume(name.clone(), volume));
+                }
+                if target.muted != device.muted {
+                    changes.push(AudioChange::SinkMute(name.clone(), target.muted));
+                }
+            }
+        }
+        for (name, target) in self.sources.iter() {
+            if let Some(device) = current.sources.get(name) {
+                if let Some(volume) = volume_change(&target.volume, &device.volume) {
+                    changes.push(AudioChange::SourceVolume(name.clone(), volume));
+                }
+                if target.muted != device.muted {
+                    changes.push(AudioChange::SourceMute(name.clone(), target.muted));
+                }
+            }
+        }
+        for (application, targets) in self.input_streams.iter() {
+            let Some(streams) = current.input_streams.get(application) else {
+                continue;
+            };
+            for (target, stream) in pair_streams(targets, streams) {
+                if target.device != stream.device && current.sinks.contains_key(&target.device) {
+                    changes.push(AudioChange::InputStreamSink(
+                        stream.index,
+                        target.device.clone(),
+                    ));
+                }
+                if let Some(volume) = volume_change(&target.volume, &stream.volume) {
+                    changes.push(AudioChange::InputStreamVolume(stream.index, volume));
+                }
+                if target.muted != stream.muted {
+                    changes.push(AudioChange::InputStreamMute(stream.index, target.muted));
+                }
+            }
+        }
+        for (application, targets) in self.output_streams.iter() {
+            let Some(streams) = current.output_streams.get(application) else {
+                continue;
+            };
+            for (target, stream) in pair_streams(targets, streams) {
+                if target.device != stream.device && current.sources.contains_key(&target.device) {
+                    changes.push(AudioChange::OutputStreamSource(
+                        stream.index,
+                        target.device.clone(),
+                    ));
+                }
+                if let Some(volume) = volume_change(&target.volume, &stream.volume) {
+                    changes.push(AudioChange::OutputStreamVolume(stream.index, volume));
+                }
+                if target.muted != stream.muted {
+                    changes.push(AudioChange::OutputStreamMute(stream.index, target.muted));
+                }
+            }
+        }
+        changes
+    }
+
+    pub fn to_toml(&self) -> Table {
+        let mut table = Table::new();
+        table.insert(
+            "default_sink".into(),
+            Value::String(self.default_sink.clone()),
+        );
+        table.insert(
+            "default_source".into(),
+            Value::String(self.default_source.clone()),
+        );
+        table.insert("sinks".into(), devices_to_toml(&self.sinks));
+        table.insert("sources".into(), devices_to_toml(&self.sources));
+        table.insert(
+            "card_profiles".into(),
+            Value::Table(
+                self.card_profiles
+                    .iter()
+                    .map(|(card, profile)| (card.clone(), Value::String(profile.clone())))
+                    .collect(),
+            ),
+        );
+        table.insert("input_streams".into(), streams_to_toml(&self.input_streams));
+        table.insert(
+            "output_streams".into(),
+            streams_to_toml(&self.output_streams),
+        );
+        table
+    }
+
+    /// Missing or malformed entries are skipped.
+    pub fn from_toml(table: &Table) -> Self {
+        let card_profiles = if let Some(Value::Table(cards)) = table.get("card_profiles") {
+            cards
+                .iter()
+                .filter_map(|(card, profile)| Some((card.clone(), profile.as_str()?.to_string())))
+                .collect()
+        } else {
+            BTreeMap::new()
+        };
+        Self {
+            default_sink: get_string(table, "default_sink"),
+            default_source: get_string(table, "default_source"),
+            sinks: devices_from_toml(table.get("sinks")),
+            sources: devices_from_toml(table.get("sources")),
+            card_profiles,
+            input_streams: streams_from_toml(table.get("input_streams")),
+            output_streams: streams_from_toml(table.get("output_streams")),
+        }
+    }
+
+    pub fn save(&self, name: &str) -> bool {
+        write_config_value(SNAPSHOT_CATEGORY, name, Value::Table(self.to_toml()))
+    }
+
+    pub fn load(name: &str) -> Option<Self> {
+        let config = parse_config();
+        let snapshot = config.get(SNAPSHOT_CATEGORY)?.get(name)?.as_table()?;
+        Some(AudioSnapshot::from_toml(snapshot))
+    }
+
+    pub fn saved_names() -> Vec<String> {
+        let config = parse_config();
+        if let Some(Value::Table(snapshots)) = config.get(SNAPSHOT_CATEGORY) {
+            snapshots.keys().cloned().collect()
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+impl DeviceSnapshot {
+    fn new(volume: &ChannelVolumes, muted: bool) -> Self {
+        Self {
+            volume: volume.clone(),
+            muted,
+        }
+    }
+}
+
+fn group_streams(
+    streams: impl Iterator<Item = (String, StreamSnapshot)>,
+) -> BTreeMap<String, Vec<StreamSnapshot>> {
+    let mut groups: BTreeMap<String, Vec<StreamSnapshot>> = BTreeMap::new();
+    for (application, stream) in streams {
+        groups.entry(application).or_default().push(stream);
+    }
+    for streams in groups.values_mut() {
+        streams.sort_by_key(|stream| stream.index);
+    }
+    groups
+}
+
+/// Streams with the same media role are paired first, the rest in order of their index.
+fn pair_streams<'a>(
+    targets: &'a [StreamSnapshot],
+    streams: &'a [StreamSnapshot],
+) -> Vec<(&'a StreamSnapshot, &'a StreamSnapshot)> {
+    let mut used = vec![false; targets.len()];
+    let mut pairs = Vec::new();
+    let mut unmatched = Vec::new();
+    for stream in streams {
+        let position = targets
+            .iter()
+            .enumerate()
+            .position(|(i, target)| !used[i] && target.media_role == stream.media_role);
+        match position {
+            Some(position) => {
+                used[position] = true;
+                pairs.push((&targets[position], stream));
+            }
+            None => unmatched.push(stream),
+        }
+    }
+    for stream in unmatched {
+        if let Some(position) = used.iter().position(|used| !used) {
+            used[position] = true;
+            pairs.push((&targets[position], stream));
+        }
+    }
+    pairs
+}
+
+/// Snapshots taken with a different channel count only restore the loudest channel.
+fn volume_change(target: &ChannelVolumes, current: &ChannelVolumes) -> Option<ChannelVolumes> {
+    if target.is_empty() {
+        return None;
+    }
+    let volume = if target.len() == current.len() {
+        target.clone()
+    } else {
+        let mut volume = current.clone();
+        volume.scale(target.max());
+        volume
+    };
+    if volume == *current {
+        None
+    } else {
+        Some(volume)
+    }
+}
+
+fn get_string(table: &Table, key: &str) -> String {
+    table
+        .get(key)
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn get_volume(table: &Table) -> ChannelVolumes {
+    let volumes: Vec<u32> = if let Some(Value::Array(volumes)) = table.get("volume") {
+        volumes
+            .iter()
+            .filter_map(Value::as_integer)
+            .map(|volume| volume.clamp(0, u32::MAX as i64) as u32)
+            .collect()
+    } else {
+        Vec::new()
+    };
+    ChannelVolumes::from_raw(&volumes)
+}
+
+fn volume_to_toml(volume: &ChannelVolumes) -> Value {
+    Value::Array(
+        volume
+            .to_raw()
+            .into_iter()
+            .map(|volume| Value::Integer(volume as i64))
+            .collect(),
+    )
+}
+
+fn devices_to_toml(devices: &BTreeMap<String, DeviceSnapshot>) -> Value {
+    let mut table = Table::new();
+    for (name, device) in devices.iter() {
+        let mut entry = Table::new();
+        entry.insert("volume".into(), volume_to_toml(&device.volume));
+        entry.insert("muted".into(), Value::Boolean(device.muted));
+        table.insert(name.clone(), Value::Table(entry));
+    }
+    Value::Table(table)
+}
+
+fn devices_from_toml(value: Option<&Value>) -> BTreeMap<String, DeviceSnapshot> {
+    let mut devices = BTreeMap::new();
+    if let Some(Value::Table(table)) = value {
+        for (name, entry) in table.iter() {
+            if let Value::Table(entry) = entry {
+                devices.insert(
+                    name.clone(),
+                    DeviceSnapshot {
+                        volume: get_volume(entry),
+                        muted: entry
+                            .get("muted")
+                            .and_then(Value::as_bool)
+                            .unwrap_or_default(),
+                    },
+                );
+            }
+        }
+    }
+    devices
+}
+
+fn streams_to_toml(streams: &BTreeMap<String, Vec<StreamSnapshot>>) -> Value {
+    let mut table = Table::new();
+    for (application, streams) in streams.iter() {
+        let entries = streams
+            .iter()
+            .map(|stream| {
+                let mut entry = Table::new();
+                entry.insert(
+                    "media_role".into(),
+                    Value::String(stream.media_role.clone()),
+                );
+                entry.insert("device".into(), Value::String(stream.device.clone()));
+                entry.insert("volume".into(), volume_to_toml(&stream.volume));
+                entry.insert("muted".into(), Value::Boolean(stream.muted));
+                Value::Table(entry)
+            })
+            .collect();
+        table.insert(application.clone(), Value::Array(entries));
+    }
+    Value::Table(table)
+}
+
+fn streams_from_toml(value: Option<&Value>) -> BTreeMap<String, Vec<StreamSnapshot>> {
+    let mut streams = BTreeMap::new();
+    if let Some(Value::Table(table)) = value {
+        for (application, entries) in table.iter() {
+            let Value::Array(entries) = entries else {
+                continue;
+            };
+            let entries = entries
+                .iter()
+                .filter_map(Value::as_table)
+                .map(|entry| StreamSnapshot {
+                    index: 0,
+                    media_role: get_string(entry, "media_role"),
+                    device: get_string(entry, "device"),
+                    volume: get_volume(entry),
+                    muted: entry
+                        .get("muted")
+                        .and_then(Value::as_bool)
+                        .unwrap_or_default(),
+                })
+                .collect();
+            streams.insert(application.clone(), entries);
+        }
+    }
+    streams
+}
+
+#[test]
+fn snapshot_toml_test() {
+    use super::audio_structures::StreamMetadata;
+
+    let sink = Sink {
+        index: 1,
+        name: String::from("alsa_output.usb-headset"),
+        volume: ChannelVolumes::from_raw(&[32768, 32768]),
+        muted: true,
+        ..Default::default()
+    };
+    let card = Card {
+        system_name: String::from("alsa_card.usb-headset"),
+        active_profile: String::from("output:analog-stereo"),
+        ..Default::default()
+    };
+    let music = InputStream {
+        application_name: String::from("Game"),
+        sink_index: 1,
+        metadata: StreamMetadata {
+            media_role: String::from("game"),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let voice = InputStream {
+        metadata: StreamMetadata {
+            media_role: String::from("phone"),
+            ..Default::default()
+        },
+        muted: true,
+        ..music.clone()
+    };
+    let server_info = ServerInfo {
+        default_sink: sink.name.clone(),
+        ..Default::default()
+    };
+    let snapshot = AudioSnapshot::new(&server_info, &[sink], &[], &[card], &[music, voice], &[]);
+    assert_eq!(snapshot.input_streams.get("Game").unwrap().len(), 2);
+
+    let toml = snapshot.to_toml().to_string();
+    let parsed = AudioSnapshot::from_toml(&toml.parse::<Table>().unwrap());
+    assert_eq!(parsed, snapshot);
+}
+
+#[test]
+fn snapshot_diff_test() {
+    use super::audio_structures::StreamMetadata;
+
+    let headset = Sink {
+        index: 1,
+        name: String::from("alsa_output.usb-headset"),
+        volume: ChannelVolumes::from_raw(&[65536, 65536]),
+        ..Default::default()
+    };
+    let speakers = Sink {
+        index: 2,
+        name: String::from("alsa_output.pci-speakers"),
+        volume: ChannelVolumes::from_raw(&[65536, 65536]),
+        ..Default::default()
+    };
+    let card = Card {
+        system_name: String::from("alsa_card.usb-headset"),
+        active_profile: String::from("output:analog-stereo"),
+        ..Default::default()
+    };
+    let first_tab = InputStream {
+        index: 10,
+        application_name: String::from("Firefox"),
+        sink_index: 2,
+        volume: ChannelVolumes::from_raw(&[65536, 65536]),
+        ..Default::default()
+    };
+    let second_tab = InputStream {
+        index: 11,
+        ..first_tab.clone()
+    };
+    let notification = InputStream {
+        index: 12,
+        metadata: StreamMetadata {
+            media_role: String::from("event"),
+            ..Default::default()
+        },
+        ..first_tab.clone()
+    };
+    let server_info = ServerInfo {
+        default_sink: speakers.name.clone(),
+        ..Default::default()
+    };
+    let current = AudioSnapshot::new(
+        &server_info,
+        &[headset.clone(), speakers.clone()],
+        &[],
+        std::slice::from_ref(&card),
+        &[first_tab.clone(), second_tab.clone(), notification.clone()],
+        &[],
+    );
+
+    let muted_headset = Sink {
+        volume: ChannelVolumes::from_raw(&[32768, 32768]),
+        muted: true,
+        ..headset.clone()
+    };
+    let other_card = Card {
+        active_profile: String::from("output:iec958-stereo"),
+        ..card
+    };
+    // the notification was saved first and the second tab was on the headset
+    let notification = InputStream {
+        index: 3,
+        muted: true,
+        ..notification
+    };
+    let first_tab = InputStream {
+        index: 4,
+        ..first_tab
+    };
+    let second_tab = InputStream {
+        index: 5,
+        sink_index: 1,
+        ..second_tab
+    };
+    let unplugged = Sink {
+        index: 3,
+        name: String::from("unplugged"),
+        ..Default::default()
+    };
+    let server_info = ServerInfo {
+        default_sink: headset.name.clone(),
+        ..Default::default()
+    };
+    let target = AudioSnapshot::new(
+        &server_info,
+        &[muted_headset, speakers, unplugged],
+        &[],
+        &[other_card],
+        &[notification, first_tab, second_tab],
+        &[],
+    );
+    let changes = target.diff(&current);
+    assert_eq!(
+        changes,
+        vec![
+            AudioChange::DefaultSink(String::from("alsa_output.usb-headset")),
+            AudioChange::CardProfile(
+                String::from("alsa_card.usb-headset"),
+                String::from("output:iec958-stereo")
+            ),
+            AudioChange::SinkVolume(
+                String::from("alsa_output.usb-headset"),
+                ChannelVolumes::from_raw(&[32768, 32768])
+            ),
+            AudioChange::SinkMute(String::from("alsa_output.usb-headset"), true),
+            AudioChange::InputStreamSink(11, String::from("alsa_output.usb-headset")),
+            AudioChange::InputStreamMute(12, true),
+        ]
+    );
+    assert!(current.diff(&current).is_empty());
+}
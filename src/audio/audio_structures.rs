@@ -1,127 +1,1560 @@
+use std::{borrow::Cow, fmt::Display};
+
 use dbus::{
     arg::{self, Append, Arg, ArgType, Get},
     Signature,
 };
 use pulse::context::introspect::{
-    CardInfo, CardProfileInfo, SinkInfo, SinkInputInfo, SourceInfo, SourceOutputInfo,
+    CardInfo, CardPortInfo, CardProfileInfo, ModuleInfo, ServerInfo as PulseServerInfo, SinkInfo,
+    SinkInputInfo, SinkPortInfo, SourceInfo, SourceOutputInfo, SourcePortInfo,
 };
+use pulse::def::{SinkFlagSet, SourceFlagSet};
+use pulse::proplist::Proplist;
+
+use crate::network::connection::Enum;
+
+#[derive(Debug)]
+pub struct PulseError(pub &'static str);
+
+pub enum Channel {
+    MONO,
+    STEREO,
+}
+
+pub const VOLUME_MUTED: u32 = 0;
+/// Raw PulseAudio volume of 100%, no amplification or attenuation.
+pub const VOLUME_NORM: u32 = 0x10000;
+/// Highest raw volume PulseAudio accepts.
+pub const VOLUME_MAX: u32 = u32::MAX / 2;
+/// Recommended upper end for volume sliders, +11dB or roughly 153%.
+/// Volumes above this can still be set and have to be displayed gracefully.
+pub const VOLUME_UI_MAX: u32 = 99957;
+
+/// A single channel volume stored in raw PulseAudio units.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, Default)]
+pub struct Volume {
+    value: u32,
+}
+
+/// Sent over D-Bus as the plain raw volume array `au`.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct ChannelVolumes {
+    volumes: Vec<Volume>,
+}
+
+pub trait TAudioObject: Arg + for<'z> Get<'z> + Send + Sync + 'static {
+    fn alias(&self) -> String;
+    fn name(&self) -> String;
+    fn volume(&self) -> ChannelVolumes;
+    fn index(&self) -> u32;
+    fn channels(&self) -> u16;
+    fn channel_map(&self) -> ChannelMap;
+    fn sample_spec(&self) -> SampleSpec;
+    fn muted(&self) -> bool;
+    fn toggle_muted(&mut self);
+    fn active(&self) -> DeviceState;
+    fn ports(&self) -> Vec<Port>;
+    fn active_port(&self) -> String;
+    fn metadata_ref(&self) -> &DeviceMetadata;
+
+    fn metadata(&self) -> DeviceMetadata {
+        self.metadata_ref().clone()
+    }
+
+    fn icon_name(&self) -> String {
+        self.metadata_ref().icon_name.clone()
+    }
+
+    fn form_factor(&self) -> String {
+        self.metadata_ref().form_factor.clone()
+    }
+
+    fn bus(&self) -> String {
+        self.metadata_ref().bus.clone()
+    }
+
+    fn monitor_name(&self) -> String {
+        self.metadata_ref().monitor_name.clone()
+    }
+
+    fn base_volume(&self) -> Volume {
+        self.metadata_ref().base_volume
+    }
+
+    fn hardware_volume(&self) -> bool {
+        self.metadata_ref().hardware_volume
+    }
+
+    fn decibel_volume(&self) -> bool {
+        self.metadata_ref().decibel_volume
+    }
+
+    fn latency(&self) -> u64 {
+        self.metadata_ref().latency
+    }
+
+    fn formats(&self) -> Vec<FormatEncoding> {
+        self.metadata_ref().formats.clone()
+    }
+
+    fn card_index(&self) -> Option<u32> {
+        match self.metadata_ref().card_index {
+            INVALID_INDEX => None,
+            index => Some(index),
+        }
+    }
+}
+
+pub trait TAudioStreamObject: Arg + for<'z> Get<'z> + Send + Sync + 'static {
+    fn index(&self) -> u32;
+    fn name(&self) -> String;
+    fn application_name(&self) -> String;
+    fn audio_object_index(&self) -> u32;
+    fn channels(&self) -> u16;
+    fn channel_map(&self) -> ChannelMap;
+    fn sample_spec(&self) -> SampleSpec;
+    fn volume(&self) -> ChannelVolumes;
+    fn muted(&self) -> bool;
+    fn toggle_muted(&mut self);
+    fn corked(&self) -> bool;
+    fn metadata_ref(&self) -> &StreamMetadata;
+
+    fn metadata(&self) -> StreamMetadata {
+        self.metadata_ref().clone()
+    }
+
+    fn icon_name(&self) -> String {
+        self.metadata_ref().icon_name.clone()
+    }
+
+    fn process_id(&self) -> Option<u32> {
+        match self.metadata_ref().process_id {
+            0 => None,
+            id => Some(id),
+        }
+    }
+
+    fn process_binary(&self) -> String {
+        self.metadata_ref().process_binary.clone()
+    }
+
+    fn media_role(&self) -> String {
+        self.metadata_ref().media_role.clone()
+    }
+
+    fn media_name(&self) -> String {
+        self.metadata_ref().media_name.clone()
+    }
+
+    fn kind(&self) -> StreamKind {
+        self.metadata_ref().kind
+    }
+
+    fn format(&self) -> FormatEncoding {
+        self.metadata_ref().format
+    }
+}
+
+impl Volume {
+    /// Creates a volume from a percentage between 0 and 100.
+    /// Use [`Volume::from_percent`] for amplified volumes.
+    pub fn from_i32(value: i32) -> Option<Self> {
+        match value {
+            i if i < 0 => None,
+            i if i > 100 => None,
+            _ => Some(Volume::from_percent(value as f64)),
+        }
+    }
+
+    /// Adds percentage points to the volume, the result is clamped between 0 and 100.
+    pub fn add(&mut self, value: i32) {
+        let temporary = self.percent().round() as i32 + value;
+        match temporary {
+            i if i > 100 => *self = Volume::from_raw(VOLUME_NORM),
+            i if i < 0 => *self = Volume::from_raw(VOLUME_MUTED),
+            _ => *self = Volume::from_percent(temporary as f64),
+        }
+    }
+
+    pub fn from_raw(value: u32) -> Self {
+        Volume {
+            value: value.min(VOLUME_MAX),
+        }
+    }
+
+    pub fn raw(&self) -> u32 {
+        self.value
+    }
+
+    /// Percentages above 100 amplify the signal.
+    pub fn from_percent(percent: f64) -> Self {
+        if percent.is_nan() || percent <= 0.0 {
+            return Volume::from_raw(VOLUME_MUTED);
+        }
+        Volume::from_raw(
+            (percent * VOLUME_NORM as f64 / 100.0)
+                .round()
+                .min(VOLUME_MAX as f64) as u32,
+        )
+    }
+
+    pub fn percent(&self) -> f64 {
+        self.value as f64 * 100.0 / VOLUME_NORM as f64
+    }
+
+    /// Creates a volume from a linear amplitude factor, 1.0 being 100%.
+    pub fn from_linear(factor: f64) -> Self {
+        if factor.is_nan() || factor <= 0.0 {
+            return Volume::from_raw(VOLUME_MUTED);
+        }
+        Volume::from_raw(
+            (factor.cbrt() * VOLUME_NORM as f64)
+                .round()
+                .min(VOLUME_MAX as f64) as u32,
+        )
+    }
+
+    /// PulseAudio maps raw volumes cubically onto the amplitude factor.
+    pub fn linear(&self) -> f64 {
+        let factor = self.value as f64 / VOLUME_NORM as f64;
+        factor * factor * factor
+    }
+
+    /// Negative infinity results in a muted volume.
+    pub fn from_db(decibel: f64) -> Self {
+        Volume::from_linear(10.0_f64.powf(decibel / 20.0))
+    }
+
+    /// Returns negative infinity for a muted volume.
+    pub fn db(&self) -> f64 {
+        if self.value == VOLUME_MUTED {
+            return f64::NEG_INFINITY;
+        }
+        20.0 * self.linear().log10()
+    }
+
+    pub fn is_muted(&self) -> bool {
+        self.value == VOLUME_MUTED
+    }
+
+    pub fn is_amplified(&self) -> bool {
+        self.value > VOLUME_NORM
+    }
+}
+
+impl From<pulse::volume::Volume> for Volume {
+    fn from(value: pulse::volume::Volume) -> Self {
+        Volume::from_raw(value.0)
+    }
+}
+
+impl From<Volume> for pulse::volume::Volume {
+    fn from(value: Volume) -> Self {
+        pulse::volume::Volume(value.value)
+    }
+}
+
+impl ChannelVolumes {
+    pub fn new(channels: u16, volume: Volume) -> Self {
+        Self {
+            volumes: vec![volume; channels as usize],
+        }
+    }
+
+    pub fn from_raw(volumes: &[u32]) -> Self {
+        Self {
+            volumes: volumes
+                .iter()
+                .map(|value| Volume::from_raw(*value))
+                .collect(),
+        }
+    }
+
+    pub fn to_raw(&self) -> Vec<u32> {
+        self.volumes.iter().map(|volume| volume.raw()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.volumes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.volumes.is_empty()
+    }
+
+    pub fn get(&self, channel: usize) -> Option<Volume> {
+        self.volumes.get(channel).copied()
+    }
+
+    pub fn volumes(&self) -> &[Volume] {
+        &self.volumes
+    }
+
+    /// Out of range channels are ignored.
+    pub fn set(&mut self, channel: usize, volume: Volume) {
+        if let Some(current) = self.volumes.get_mut(channel) {
+            *current = volume;
+        }
+    }
+
+    pub fn set_all(&mut self, volume: Volume) {
+        for current in self.volumes.iter_mut() {
+            *current = volume;
+        }
+    }
+
+    /// The loudest channel, this is what volume sliders usually display.
+    pub fn max(&self) -> Volume {
+        self.volumes.iter().max().copied().unwrap_or_default()
+    }
+
+    pub fn min(&self) -> Volume {
+        self.volumes.iter().min().copied().unwrap_or_default()
+    }
+
+    pub fn avg(&self) -> Volume {
+        average(self.volumes.iter())
+    }
+
+    /// Sets the loudest channel to `max` and scales all other channels accordingly,
+    /// which keeps the balance intact.
+    pub fn scale(&mut self, max: Volume) {
+        let current = self.max();
+        if current.is_muted() {
+            self.set_all(max);
+            return;
+        }
+        for volume in self.volumes.iter_mut() {
+            *volume = Volume::from_raw(
+                (volume.raw() as u64 * max.raw() as u64 / current.raw() as u64) as u32,
+            );
+        }
+    }
+
+    /// Raises the loudest channel by `step` without going above `limit`, keeping the balance.
+    pub fn increase(&mut self, step: Volume, limit: Volume) {
+        let max = self.max();
+        if max >= limit {
+            return;
+        }
+        self.scale(Volume::from_raw(
+            max.raw().saturating_add(step.raw()).min(limit.raw()),
+        ));
+    }
+
+    /// Lowers the loudest channel by `step`, keeping the balance.
+    pub fn decrease(&mut self, step: Volume) {
+        self.scale(Volume::from_raw(
+            self.max().raw().saturating_sub(step.raw()),
+        ));
+    }
+
+    /// Returns the balance between -1.0 (only left) and 1.0 (only right).
+    /// `left` and `right` are the channel indices of each side.
+    pub fn balance(&self, left: &[usize], right: &[usize]) -> f64 {
+        self.side_ratio(left, right)
+    }
+
+    /// Sets the balance between -1.0 (only left) and 1.0 (only right)
+    /// without changing the loudest side.
+    pub fn set_balance(&mut self, left: &[usize], right: &[usize], balance: f64) {
+        self.set_side_ratio(left, right, balance);
+    }
+
+    /// Returns the fade between -1.0 (only rear) and 1.0 (only front).
+    /// `front` and `rear` are the channel indices of each side.
+    pub fn fade(&self, front: &[usize], rear: &[usize]) -> f64 {
+        self.side_ratio(rear, front)
+    }
+
+    /// Sets the fade between -1.0 (only rear) and 1.0 (only front)
+    /// without changing the louder side.
+    pub fn set_fade(&mut self, front: &[usize], rear: &[usize], fade: f64) {
+        self.set_side_ratio(rear, front, fade);
+    }
+
+    fn side_average(&self, channels: &[usize]) -> Volume {
+        average(
+            channels
+                .iter()
+                .filter_map(|channel| self.volumes.get(*channel)),
+        )
+    }
+
+    fn side_ratio(&self, negative: &[usize], positive: &[usize]) -> f64 {
+        let negative = self.side_average(negative).raw() as f64;
+        let positive = self.side_average(positive).raw() as f64;
+        if negative == positive {
+            0.0
+        } else if negative > positive {
+            positive / negative - 1.0
+        } else {
+            1.0 - negative / positive
+        }
+    }
+
+    fn set_side_ratio(&mut self, negative: &[usize], positive: &[usize], ratio: f64) {
+        let ratio = ratio.clamp(-1.0, 1.0);
+        let current_negative = self.side_average(negative);
+        let current_positive = self.side_average(positive);
+        let max = current_negative.max(current_positive).raw() as f64;
+        let (new_negative, new_positive) = if ratio <= 0.0 {
+            (max, (ratio + 1.0) * max)
+        } else {
+            ((1.0 - ratio) * max, max)
+        };
+        for (channels, current, new) in [
+            (negative, current_negative, new_negative),
+            (positive, current_positive, new_positive),
+        ] {
+            for channel in channels {
+                if let Some(volume) = self.volumes.get_mut(*channel) {
+                    let value = if current.is_muted() {
+                        new
+                    } else {
+                        volume.raw() as f64 * new / current.raw() as f64
+                    };
+                    *volume = Volume::from_raw(value.round() as u32);
+                }
+            }
+        }
+    }
+}
+
+fn average<'a>(volumes: impl Iterator<Item = &'a Volume>) -> Volume {
+    let (sum, count) = volumes.fold((0_u64, 0_u64), |(sum, count), volume| {
+        (sum + volume.raw() as u64, count + 1)
+    });
+    if count == 0 {
+        return Volume::default();
+    }
+    Volume::from_raw((sum / count) as u32)
+}
+
+impl Append for Volume {
+    fn append_by_ref(&self, iter: &mut arg::IterAppend) {
+        iter.append(self.value);
+    }
+}
+
+impl<'a> Get<'a> for Volume {
+    fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
+        Some(Volume::from_raw(u32::get(i)?))
+    }
+}
+
+impl Arg for Volume {
+    const ARG_TYPE: arg::ArgType = ArgType::UInt32;
+    fn signature() -> Signature<'static> {
+        unsafe { Signature::from_slice_unchecked("u\0") }
+    }
+}
+
+impl From<&pulse::volume::ChannelVolumes> for ChannelVolumes {
+    fn from(value: &pulse::volume::ChannelVolumes) -> Self {
+        Self {
+            volumes: value
+                .get()
+                .iter()
+                .map(|volume| Volume::from(*volume))
+                .collect(),
+        }
+    }
+}
+
+impl From<&ChannelVolumes> for pulse::volume::ChannelVolumes {
+    fn from(value: &ChannelVolumes) -> Self {
+        let mut volumes = pulse::volume::ChannelVolumes::default();
+        let channels = value
+            .len()
+            .min(pulse::volume::ChannelVolumes::CHANNELS_MAX as usize);
+        volumes.set_len(channels as u8);
+        for (volume, new) in volumes.get_mut().iter_mut().zip(value.volumes.iter()) {
+            *volume = (*new).into();
+        }
+        volumes
+    }
+}
+
+impl Append for ChannelVolumes {
+    fn append_by_ref(&self, iter: &mut arg::IterAppend) {
+        iter.append(self.to_raw());
+    }
+}
+
+impl<'a> Get<'a> for ChannelVolumes {
+    fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
+        let volumes = <Vec<u32>>::get(i)?;
+        Some(ChannelVolumes::from_raw(&volumes))
+    }
+}
+
+impl Arg for ChannelVolumes {
+    const ARG_TYPE: arg::ArgType = ArgType::Array;
+    fn signature() -> Signature<'static> {
+        unsafe { Signature::from_slice_unchecked("au\0") }
+    }
+}
+
+#[test]
+fn volume_test() {
+    let failvolume = Volume::from_i32(101);
+    assert!(failvolume.is_none());
+    let failvolume = Volume::from_i32(-1);
+    assert!(failvolume.is_none());
+    let volume = Volume::from_i32(78);
+    assert!(volume.is_some());
+    let mut volume = volume.unwrap();
+    volume.add(200);
+    assert_eq!(Volume::from_i32(100).unwrap(), volume);
+}
+
+#[test]
+fn volume_conversion_test() {
+    let volume = Volume::from_raw(VOLUME_NORM);
+    assert_eq!(volume.percent(), 100.0);
+    assert_eq!(volume.linear(), 1.0);
+    assert_eq!(volume.db(), 0.0);
+    assert!(!volume.is_amplified());
+    assert_eq!(Volume::from_percent(50.0).raw(), VOLUME_NORM / 2);
+    assert_eq!(Volume::from_linear(0.125).raw(), VOLUME_NORM / 2);
+    assert_eq!(Volume::from_db(0.0), volume);
+    assert_eq!(
+        Volume::from_db(f64::NEG_INFINITY),
+        Volume::from_raw(VOLUME_MUTED)
+    );
+    assert_eq!(Volume::from_db(-6.0).db().round(), -6.0);
+    assert_eq!(Volume::from_raw(VOLUME_UI_MAX).db().round(), 11.0);
+    assert!(Volume::from_percent(150.0).is_amplified());
+    assert_eq!(Volume::from_raw(u32::MAX).raw(), VOLUME_MAX);
+}
+
+#[test]
+fn channel_volumes_test() {
+    let mut volumes = ChannelVolumes::from_raw(&[VOLUME_NORM, VOLUME_NORM / 2]);
+    assert_eq!(volumes.max().raw(), VOLUME_NORM);
+    assert_eq!(volumes.balance(&[0], &[1]), -0.5);
+    volumes.scale(Volume::from_percent(150.0));
+    assert_eq!(
+        volumes.to_raw(),
+        vec![VOLUME_NORM * 3 / 2, VOLUME_NORM * 3 / 4]
+    );
+    assert_eq!(volumes.balance(&[0], &[1]), -0.5);
+    volumes.set_balance(&[0], &[1], 0.0);
+    assert_eq!(
+        volumes.to_raw(),
+        vec![VOLUME_NORM * 3 / 2, VOLUME_NORM * 3 / 2]
+    );
+    volumes.decrease(Volume::from_percent(50.0));
+    assert_eq!(volumes.to_raw(), vec![VOLUME_NORM, VOLUME_NORM]);
+    volumes.increase(Volume::from_percent(100.0), Volume::from_raw(VOLUME_UI_MAX));
+    assert_eq!(volumes.max().raw(), VOLUME_UI_MAX);
+
+    let mut surround = ChannelVolumes::new(4, Volume::from_raw(VOLUME_NORM));
+    surround.set_fade(&[0, 1], &[2, 3], 0.5);
+    assert_eq!(surround.fade(&[0, 1], &[2, 3]), 0.5);
+    assert_eq!(surround.get(2).unwrap().raw(), VOLUME_NORM / 2);
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChannelPosition {
+    #[default]
+    Invalid,
+    Mono,
+    FrontLeft,
+    FrontRight,
+    FrontCenter,
+    RearCenter,
+    RearLeft,
+    RearRight,
+    Lfe,
+    FrontLeftOfCenter,
+    FrontRightOfCenter,
+    SideLeft,
+    SideRight,
+    Aux(u8),
+    TopCenter,
+    TopFrontLeft,
+    TopFrontRight,
+    TopFrontCenter,
+    TopRearLeft,
+    TopRearRight,
+    TopRearCenter,
+}
+
+impl Enum for ChannelPosition {
+    fn from_i32(num: i32) -> Self {
+        match num {
+            0 => ChannelPosition::Mono,
+            1 => ChannelPosition::FrontLeft,
+            2 => ChannelPosition::FrontRight,
+            3 => ChannelPosition::FrontCenter,
+            4 => ChannelPosition::RearCenter,
+            5 => ChannelPosition::RearLeft,
+            6 => ChannelPosition::RearRight,
+            7 => ChannelPosition::Lfe,
+            8 => ChannelPosition::FrontLeftOfCenter,
+            9 => ChannelPosition::FrontRightOfCenter,
+            10 => ChannelPosition::SideLeft,
+            11 => ChannelPosition::SideRight,
+            12..=43 => ChannelPosition::Aux((num - 12) as u8),
+            44 => ChannelPosition::TopCenter,
+            45 => ChannelPosition::TopFrontLeft,
+            46 => ChannelPosition::TopFrontRight,
+            47 => ChannelPosition::TopFrontCenter,
+            48 => ChannelPosition::TopRearLeft,
+            49 => ChannelPosition::TopRearRight,
+            50 => ChannelPosition::TopRearCenter,
+            _ => ChannelPosition::Invalid,
+        }
+    }
+
+    fn to_i32(&self) -> i32 {
+        match self {
+            ChannelPosition::Invalid => -1,
+            ChannelPosition::Mono => 0,
+            ChannelPosition::FrontLeft => 1,
+            ChannelPosition::FrontRight => 2,
+            ChannelPosition::FrontCenter => 3,
+            ChannelPosition::RearCenter => 4,
+            ChannelPosition::RearLeft => 5,
+            ChannelPosition::RearRight => 6,
+            ChannelPosition::Lfe => 7,
+            ChannelPosition::FrontLeftOfCenter => 8,
+            ChannelPosition::FrontRightOfCenter => 9,
+            ChannelPosition::SideLeft => 10,
+            ChannelPosition::SideRight => 11,
+            ChannelPosition::Aux(aux) if *aux < 32 => 12 + *aux as i32,
+            ChannelPosition::Aux(_) => -1,
+            ChannelPosition::TopCenter => 44,
+            ChannelPosition::TopFrontLeft => 45,
+            ChannelPosition::TopFrontRight => 46,
+            ChannelPosition::TopFrontCenter => 47,
+            ChannelPosition::TopRearLeft => 48,
+            ChannelPosition::TopRearRight => 49,
+            ChannelPosition::TopRearCenter => 50,
+        }
+    }
+}
+
+impl Display for ChannelPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChannelPosition::Invalid => f.write_str("Invalid"),
+            ChannelPosition::Mono => f.write_str("Mono"),
+            ChannelPosition::FrontLeft => f.write_str("Front Left"),
+            ChannelPosition::FrontRight => f.write_str("Front Right"),
+            ChannelPosition::FrontCenter => f.write_str("Front Center"),
+            ChannelPosition::RearCenter => f.write_str("Rear Center"),
+            ChannelPosition::RearLeft => f.write_str("Rear Left"),
+            ChannelPosition::RearRight => f.write_str("Rear Right"),
+            ChannelPosition::Lfe => f.write_str("Subwoofer"),
+            ChannelPosition::FrontLeftOfCenter => f.write_str("Front Left-of-center"),
+            ChannelPosition::FrontRightOfCenter => f.write_str("Front Right-of-center"),
+            ChannelPosition::SideLeft => f.write_str("Side Left"),
+            ChannelPosition::SideRight => f.write_str("Side Right"),
+            ChannelPosition::Aux(aux) => write!(f, "Auxiliary {}", aux),
+            ChannelPosition::TopCenter => f.write_str("Top Center"),
+            ChannelPosition::TopFrontLeft => f.write_str("Top Front Left"),
+            ChannelPosition::TopFrontRight => f.write_str("Top Front Right"),
+            ChannelPosition::TopFrontCenter => f.write_str("Top Front Center"),
+            ChannelPosition::TopRearLeft => f.write_str("Top Rear Left"),
+            ChannelPosition::TopRearRight => f.write_str("Top Rear Right"),
+            ChannelPosition::TopRearCenter => f.write_str("Top Rear Center"),
+        }
+    }
+}
+
+impl ChannelPosition {
+    pub fn is_left(&self) -> bool {
+        matches!(
+            self,
+            ChannelPosition::FrontLeft
+                | ChannelPosition::RearLeft
+                | ChannelPosition::FrontLeftOfCenter
+                | ChannelPosition::SideLeft
+                | ChannelPosition::TopFrontLeft
+                | ChannelPosition::TopRearLeft
+        )
+    }
+
+    pub fn is_right(&self) -> bool {
+        matches!(
+            self,
+            ChannelPosition::FrontRight
+                | ChannelPosition::RearRight
+                | ChannelPosition::FrontRightOfCenter
+                | ChannelPosition::SideRight
+                | ChannelPosition::TopFrontRight
+                | ChannelPosition::TopRearRight
+        )
+    }
+
+    pub fn is_front(&self) -> bool {
+        matches!(
+            self,
+            ChannelPosition::FrontLeft
+                | ChannelPosition::FrontRight
+                | ChannelPosition::FrontCenter
+                | ChannelPosition::FrontLeftOfCenter
+                | ChannelPosition::FrontRightOfCenter
+                | ChannelPosition::TopFrontLeft
+                | ChannelPosition::TopFrontRight
+                | ChannelPosition::TopFrontCenter
+        )
+    }
+
+    pub fn is_rear(&self) -> bool {
+        matches!(
+            self,
+            ChannelPosition::RearLeft
+                | ChannelPosition::RearRight
+                | ChannelPosition::RearCenter
+                | ChannelPosition::TopRearLeft
+                | ChannelPosition::TopRearRight
+                | ChannelPosition::TopRearCenter
+        )
+    }
+
+    pub fn is_lfe(&self) -> bool {
+        *self == ChannelPosition::Lfe
+    }
+
+    /// Name used by pulse in module arguments, e.g. "front-left".
+    pub fn system_name(&self) -> String {
+        match self {
+            ChannelPosition::Invalid => String::from("invalid"),
+            ChannelPosition::Mono => String::from("mono"),
+            ChannelPosition::FrontLeft => String::from("front-left"),
+            ChannelPosition::FrontRight => String::from("front-right"),
+            ChannelPosition::FrontCenter => String::from("front-center"),
+            ChannelPosition::RearCenter => String::from("rear-center"),
+            ChannelPosition::RearLeft => String::from("rear-left"),
+            ChannelPosition::RearRight => String::from("rear-right"),
+            ChannelPosition::Lfe => String::from("lfe"),
+            ChannelPosition::FrontLeftOfCenter => String::from("front-left-of-center"),
+            ChannelPosition::FrontRightOfCenter => String::from("front-right-of-center"),
+            ChannelPosition::SideLeft => String::from("side-left"),
+            ChannelPosition::SideRight => String::from("side-right"),
+            ChannelPosition::Aux(aux) => format!("aux{}", aux),
+            ChannelPosition::TopCenter => String::from("top-center"),
+            ChannelPosition::TopFrontLeft => String::from("top-front-left"),
+            ChannelPosition::TopFrontRight => String::from("top-front-right"),
+            ChannelPosition::TopFrontCenter => String::from("top-front-center"),
+            ChannelPosition::TopRearLeft => String::from("top-rear-left"),
+            ChannelPosition::TopRearRight => String::from("top-rear-right"),
+            ChannelPosition::TopRearCenter => String::from("top-rear-center"),
+        }
+    }
+}
+
+impl From<pulse::channelmap::Position> for ChannelPosition {
+    fn from(value: pulse::channelmap::Position) -> Self {
+        ChannelPosition::from_i32(value as i32)
+    }
+}
+
+impl Append for ChannelPosition {
+    fn append_by_ref(&self, iter: &mut arg::IterAppend) {
+        iter.append(self.to_i32());
+    }
+}
+
+impl<'a> Get<'a> for ChannelPosition {
+    fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
+        Some(ChannelPosition::from_i32(i32::get(i)?))
+    }
+}
+
+impl Arg for ChannelPosition {
+    const ARG_TYPE: arg::ArgType = ArgType::Int32;
+    fn signature() -> Signature<'static> {
+        unsafe { Signature::from_slice_unchecked("i\0") }
+    }
+}
+
+/// Positions of every channel of a device or stream, in the same order as its [`ChannelVolumes`].
+/// Sent over D-Bus as the array of pulse position values `ai`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChannelMap {
+    pub positions: Vec<ChannelPosition>,
+}
+
+impl ChannelMap {
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    pub fn get(&self, channel: usize) -> Option<ChannelPosition> {
+        self.positions.get(channel).copied()
+    }
+
+    pub fn find(&self, position: ChannelPosition) -> Option<usize> {
+        self.positions
+            .iter()
+            .position(|current| *current == position)
+    }
+
+    pub fn left(&self) -> Vec<usize> {
+        self.channels_matching(ChannelPosition::is_left)
+    }
+
+    pub fn right(&self) -> Vec<usize> {
+        self.channels_matching(ChannelPosition::is_right)
+    }
+
+    pub fn front(&self) -> Vec<usize> {
+        self.channels_matching(ChannelPosition::is_front)
+    }
+
+    pub fn rear(&self) -> Vec<usize> {
+        self.channels_matching(ChannelPosition::is_rear)
+    }
+
+    pub fn can_balance(&self) -> bool {
+        !self.left().is_empty() && !self.right().is_empty()
+    }
+
+    pub fn can_fade(&self) -> bool {
+        !self.front().is_empty() && !self.rear().is_empty()
+    }
+
+    fn channels_matching(&self, filter: fn(&ChannelPosition) -> bool) -> Vec<usize> {
+        self.positions
+            .iter()
+            .enumerate()
+            .filter(|(_, position)| filter(position))
+            .map(|(channel, _)| channel)
+            .collect()
+    }
+}
+
+impl From<&pulse::channelmap::Map> for ChannelMap {
+    fn from(value: &pulse::channelmap::Map) -> Self {
+        Self {
+            positions: value
+                .get()
+                .iter()
+                .map(|position| ChannelPosition::from(*position))
+                .collect(),
+        }
+    }
+}
+
+impl Append for ChannelMap {
+    fn append_by_ref(&self, iter: &mut arg::IterAppend) {
+        iter.append(&self.positions);
+    }
+}
+
+impl<'a> Get<'a> for ChannelMap {
+    fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
+        let positions = <Vec<ChannelPosition>>::get(i)?;
+        Some(ChannelMap { positions })
+    }
+}
+
+impl Arg for ChannelMap {
+    const ARG_TYPE: arg::ArgType = ArgType::Array;
+    fn signature() -> Signature<'static> {
+        unsafe { Signature::from_slice_unchecked("ai\0") }
+    }
+}
+
+#[test]
+fn channel_map_test() {
+    let map = ChannelMap {
+        positions: vec![
+            ChannelPosition::FrontLeft,
+            ChannelPosition::FrontRight,
+            ChannelPosition::RearLeft,
+            ChannelPosition::RearRight,
+            ChannelPosition::Lfe,
+        ],
+    };
+    assert_eq!(map.left(), vec![0, 2]);
+    assert_eq!(map.right(), vec![1, 3]);
+    assert_eq!(map.front(), vec![0, 1]);
+    assert_eq!(map.rear(), vec![2, 3]);
+    assert_eq!(map.find(ChannelPosition::Lfe), Some(4));
+    assert!(map.can_balance() && map.can_fade());
+    let mono = ChannelMap {
+        positions: vec![ChannelPosition::Mono],
+    };
+    assert!(!mono.can_balance());
+    assert_eq!(ChannelPosition::from_i32(15), ChannelPosition::Aux(3));
+    assert_eq!(ChannelPosition::Aux(3).to_i32(), 15);
+    assert_eq!(ChannelPosition::from_i32(-1), ChannelPosition::Invalid);
+}
+
+#[test]
+fn sink_dbus_test() {
+    let sink = Sink {
+        channels: 2,
+        channel_map: ChannelMap {
+            positions: vec![ChannelPosition::FrontLeft, ChannelPosition::FrontRight],
+        },
+        volume: ChannelVolumes::new(2, Volume::from_raw(VOLUME_NORM)),
+        ports: vec![Port {
+            name: String::from("analog-output-headphones"),
+            description: String::from("Headphones"),
+            priority: 9900,
+            available: Availability::Yes,
+        }],
+        active_port: String::from("analog-output-headphones"),
+        ..Default::default()
+    };
+    let message = dbus::Message::new_signal("/test", "org.test", "Test")
+        .unwrap()
+        .append1(&sink);
+    assert_eq!(
+        message.iter_init().signature().to_string(),
+        "(ussqai(iuy)aubia(ssui)s(ssssubbtuai))"
+    );
+    let received: Sink = message.read1().unwrap();
+    assert_eq!(received.channel_map, sink.channel_map);
+    assert_eq!(received.volume, sink.volume);
+    assert_eq!(received.ports, sink.ports);
+    assert_eq!(received.active_port, sink.active_port);
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SampleFormat {
+    #[default]
+    Invalid,
+    U8,
+    ALaw,
+    ULaw,
+    S16le,
+    S16be,
+    F32le,
+    F32be,
+    S32le,
+    S32be,
+    S24le,
+    S24be,
+    S24_32le,
+    S24_32be,
+}
+
+impl Enum for SampleFormat {
+    fn from_i32(num: i32) -> Self {
+        match num {
+            0 => SampleFormat::U8,
+            1 => SampleFormat::ALaw,
+            2 => SampleFormat::ULaw,
+            3 => SampleFormat::S16le,
+            4 => SampleFormat::S16be,
+            5 => SampleFormat::F32le,
+            6 => SampleFormat::F32be,
+            7 => SampleFormat::S32le,
+            8 => SampleFormat::S32be,
+            9 => SampleFormat::S24le,
+            10 => SampleFormat::S24be,
+            11 => SampleFormat::S24_32le,
+            12 => SampleFormat::S24_32be,
+            _ => SampleFormat::Invalid,
+        }
+    }
+
+    fn to_i32(&self) -> i32 {
+        match self {
+            SampleFormat::Invalid => -1,
+            SampleFormat::U8 => 0,
+            SampleFormat::ALaw => 1,
+            SampleFormat::ULaw => 2,
+            SampleFormat::S16le => 3,
+            SampleFormat::S16be => 4,
+            SampleFormat::F32le => 5,
+            SampleFormat::F32be => 6,
+            SampleFormat::S32le => 7,
+            SampleFormat::S32be => 8,
+            SampleFormat::S24le => 9,
+            SampleFormat::S24be => 10,
+            SampleFormat::S24_32le => 11,
+            SampleFormat::S24_32be => 12,
+        }
+    }
+}
+
+impl Display for SampleFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SampleFormat::Invalid => f.write_str("invalid"),
+            SampleFormat::U8 => f.write_str("u8"),
+            SampleFormat::ALaw => f.write_str("aLaw"),
+            SampleFormat::ULaw => f.write_str("uLaw"),
+            SampleFormat::S16le => f.write_str("s16le"),
+            SampleFormat::S16be => f.write_str("s16be"),
+            SampleFormat::F32le => f.write_str("float32le"),
+            SampleFormat::F32be => f.write_str("float32be"),
+            SampleFormat::S32le => f.write_str("s32le"),
+            SampleFormat::S32be => f.write_str("s32be"),
+            SampleFormat::S24le => f.write_str("s24le"),
+            SampleFormat::S24be => f.write_str("s24be"),
+            SampleFormat::S24_32le => f.write_str("s24-32le"),
+            SampleFormat::S24_32be => f.write_str("s24-32be"),
+        }
+    }
+}
+
+impl SampleFormat {
+    /// Bit depth of a single sample, 0 for an invalid format.
+    pub fn bits(&self) -> u8 {
+        match self {
+            SampleFormat::Invalid => 0,
+            SampleFormat::U8 | SampleFormat::ALaw | SampleFormat::ULaw => 8,
+            SampleFormat::S16le | SampleFormat::S16be => 16,
+            SampleFormat::S24le
+            | SampleFormat::S24be
+            | SampleFormat::S24_32le
+            | SampleFormat::S24_32be => 24,
+            SampleFormat::F32le
+            | SampleFormat::F32be
+            | SampleFormat::S32le
+            | SampleFormat::S32be => 32,
+        }
+    }
+}
+
+impl From<pulse::sample::Format> for SampleFormat {
+    fn from(value: pulse::sample::Format) -> Self {
+        SampleFormat::from_i32(value as i32)
+    }
+}
+
+impl Append for SampleFormat {
+    fn append_by_ref(&self, iter: &mut arg::IterAppend) {
+        iter.append(self.to_i32());
+    }
+}
+
+impl<'a> Get<'a> for SampleFormat {
+    fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
+        Some(SampleFormat::from_i32(i32::get(i)?))
+    }
+}
+
+impl Arg for SampleFormat {
+    const ARG_TYPE: arg::ArgType = ArgType::Int32;
+    fn signature() -> Signature<'static> {
+        unsafe { Signature::from_slice_unchecked("i\0") }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SampleSpec {
+    pub format: SampleFormat,
+    /// Sample rate in Hz.
+    pub rate: u32,
+    pub channels: u8,
+}
+
+impl Display for SampleSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}ch {}Hz", self.format, self.channels, self.rate)
+    }
+}
+
+impl From<&pulse::sample::Spec> for SampleSpec {
+    fn from(value: &pulse::sample::Spec) -> Self {
+        Self {
+            format: value.format.into(),
+            rate: value.rate,
+            channels: value.channels,
+        }
+    }
+}
+
+impl Append for SampleSpec {
+    fn append_by_ref(&self, iter: &mut arg::IterAppend) {
+        iter.append_struct(|i| {
+            i.append(self.format);
+            i.append(self.rate);
+            i.append(self.channels);
+        });
+    }
+}
+
+impl<'a> Get<'a> for SampleSpec {
+    fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
+        let (format, rate, channels) = <(SampleFormat, u32, u8)>::get(i)?;
+        Some(Self {
+            format,
+            rate,
+            channels,
+        })
+    }
+}
+
+impl Arg for SampleSpec {
+    const ARG_TYPE: arg::ArgType = ArgType::Struct;
+    fn signature() -> Signature<'static> {
+        unsafe { Signature::from_slice_unchecked("(iuy)\0") }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FormatEncoding {
+    #[default]
+    Invalid,
+    Any,
+    Pcm,
+    Ac3Iec61937,
+    Eac3Iec61937,
+    MpegIec61937,
+    DtsIec61937,
+    Mpeg2AacIec61937,
+    TrueHdIec61937,
+    DtsHdIec61937,
+}
+
+impl Enum for FormatEncoding {
+    fn from_i32(num: i32) -> Self {
+        match num {
+            0 => FormatEncoding::Any,
+            1 => FormatEncoding::Pcm,
+            2 => FormatEncoding::Ac3Iec61937,
+            3 => FormatEncoding::Eac3Iec61937,
+            4 => FormatEncoding::MpegIec61937,
+            5 => FormatEncoding::DtsIec61937,
+            6 => FormatEncoding::Mpeg2AacIec61937,
+            7 => FormatEncoding::TrueHdIec61937,
+            8 => FormatEncoding::DtsHdIec61937,
+            _ => FormatEncoding::Invalid,
+        }
+    }
+
+    fn to_i32(&self) -> i32 {
+        match self {
+            FormatEncoding::Invalid => -1,
+            FormatEncoding::Any => 0,
+            FormatEncoding::Pcm => 1,
+            FormatEncoding::Ac3Iec61937 => 2,
+            FormatEncoding::Eac3Iec61937 => 3,
+            FormatEncoding::MpegIec61937 => 4,
+            FormatEncoding::DtsIec61937 => 5,
+            FormatEncoding::Mpeg2AacIec61937 => 6,
+            FormatEncoding::TrueHdIec61937 => 7,
+            FormatEncoding::DtsHdIec61937 => 8,
+        }
+    }
+}
+
+impl Display for FormatEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormatEncoding::Invalid => f.write_str("Invalid"),
+            FormatEncoding::Any => f.write_str("Any"),
+            FormatEncoding::Pcm => f.write_str("PCM"),
+            FormatEncoding::Ac3Iec61937 => f.write_str("AC3"),
+            FormatEncoding::Eac3Iec61937 => f.write_str("E-AC3"),
+            FormatEncoding::MpegIec61937 => f.write_str("MPEG"),
+            FormatEncoding::DtsIec61937 => f.write_str("DTS"),
+            FormatEncoding::Mpeg2AacIec61937 => f.write_str("MPEG-2 AAC"),
+            FormatEncoding::TrueHdIec61937 => f.write_str("TrueHD"),
+            FormatEncoding::DtsHdIec61937 => f.write_str("DTS-HD"),
+        }
+    }
+}
+
+impl From<&pulse::format::Info> for FormatEncoding {
+    fn from(value: &pulse::format::Info) -> Self {
+        FormatEncoding::from_i32(value.get_encoding() as i32)
+    }
+}
+
+impl Append for FormatEncoding {
+    fn append_by_ref(&self, iter: &mut arg::IterAppend) {
+        iter.append(self.to_i32());
+    }
+}
+
+impl<'a> Get<'a> for FormatEncoding {
+    fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
+        Some(FormatEncoding::from_i32(i32::get(i)?))
+    }
+}
+
+impl Arg for FormatEncoding {
+    const ARG_TYPE: arg::ArgType = ArgType::Int32;
+    fn signature() -> Signature<'static> {
+        unsafe { Signature::from_slice_unchecked("i\0") }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Availability {
+    #[default]
+    Unknown,
+    No,
+    Yes,
+}
+
+impl Enum for Availability {
+    fn from_i32(num: i32) -> Self {
+        match num {
+            1 => Availability::No,
+            2 => Availability::Yes,
+            _ => Availability::Unknown,
+        }
+    }
 
-use crate::network::connection::Enum;
+    fn to_i32(&self) -> i32 {
+        match self {
+            Availability::Unknown => 0,
+            Availability::No => 1,
+            Availability::Yes => 2,
+        }
+    }
+}
 
-#[derive(Debug)]
-pub struct PulseError(pub &'static str);
+impl From<pulse::def::PortAvailable> for Availability {
+    fn from(value: pulse::def::PortAvailable) -> Self {
+        Availability::from_i32(value as i32)
+    }
+}
 
-pub enum Status {
-    RUNNING,
-    SUSPENDED,
-    IDLE,
+impl Append for Availability {
+    fn append_by_ref(&self, iter: &mut arg::IterAppend) {
+        iter.append(self.to_i32());
+    }
 }
 
-pub enum Channel {
-    MONO,
-    STEREO,
+impl<'a> Get<'a> for Availability {
+    fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
+        Some(Availability::from_i32(i32::get(i)?))
+    }
 }
 
-#[derive(PartialEq, Eq, Debug)]
-pub struct Volume {
-    value: u32,
+impl Arg for Availability {
+    const ARG_TYPE: arg::ArgType = ArgType::Int32;
+    fn signature() -> Signature<'static> {
+        unsafe { Signature::from_slice_unchecked("i\0") }
+    }
 }
 
-pub trait TAudioObject: Arg + for<'z> Get<'z> + Send + Sync + 'static {
-    fn alias(&self) -> String;
-    fn name(&self) -> String;
-    fn volume(&self) -> Vec<u32>;
-    fn index(&self) -> u32;
-    fn channels(&self) -> u16;
-    fn muted(&self) -> bool;
-    fn toggle_muted(&mut self);
-    fn active(&self) -> i32;
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Port {
+    pub name: String,
+    pub description: String,
+    pub priority: u32,
+    pub available: Availability,
 }
 
-pub trait TAudioStreamObject: Arg + for<'z> Get<'z> + Send + Sync + 'static {
-    fn index(&self) -> u32;
-    fn name(&self) -> String;
-    fn application_name(&self) -> String;
-    fn audio_object_index(&self) -> u32;
-    fn channels(&self) -> u16;
-    fn volume(&self) -> Vec<u32>;
-    fn muted(&self) -> bool;
-    fn toggle_muted(&mut self);
-    fn corked(&self) -> bool;
+impl Append for Port {
+    fn append_by_ref(&self, iter: &mut arg::IterAppend) {
+        iter.append_struct(|i| {
+            i.append(&self.name);
+            i.append(&self.description);
+            i.append(self.priority);
+            i.append(self.available);
+        });
+    }
 }
 
-impl Volume {
-    pub fn from_i32(value: i32) -> Option<Self> {
-        match value {
-            i if i < 0 => None,
-            i if i > 100 => None,
-            _ => Some(Volume {
-                value: value as u32,
-            }),
+impl<'a> Get<'a> for Port {
+    fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
+        let (name, description, priority, available) =
+            <(String, String, u32, Availability)>::get(i)?;
+        Some(Self {
+            name,
+            description,
+            priority,
+            available,
+        })
+    }
+}
+
+impl Arg for Port {
+    const ARG_TYPE: arg::ArgType = ArgType::Struct;
+    fn signature() -> Signature<'static> {
+        unsafe { Signature::from_slice_unchecked("(ssui)\0") }
+    }
+}
+
+impl From<&SinkPortInfo<'_>> for Port {
+    fn from(value: &SinkPortInfo<'_>) -> Self {
+        let name = if let Some(name_opt) = &value.name {
+            String::from(name_opt.clone())
+        } else {
+            String::from("")
+        };
+        let description = if let Some(description_opt) = &value.description {
+            String::from(description_opt.clone())
+        } else {
+            String::from("")
+        };
+        Self {
+            name,
+            description,
+            priority: value.priority,
+            available: value.available.into(),
         }
     }
+}
 
-    pub fn add(&mut self, value: i32) {
-        let temporary = self.value as i32 + value;
-        match temporary {
-            i if i > 100 => self.value = 100,
-            i if i < 0 => self.value = 0,
-            _ => self.value = temporary as u32,
+impl From<&SourcePortInfo<'_>> for Port {
+    fn from(value: &SourcePortInfo<'_>) -> Self {
+        let name = if let Some(name_opt) = &value.name {
+            String::from(name_opt.clone())
+        } else {
+            String::from("")
+        };
+        let description = if let Some(description_opt) = &value.description {
+            String::from(description_opt.clone())
+        } else {
+            String::from("")
+        };
+        Self {
+            name,
+            description,
+            priority: value.priority,
+            available: value.available.into(),
         }
     }
 }
 
-#[test]
-fn volume_test() {
-    let failvolume = Volume::from_i32(101);
-    assert!(failvolume.is_none());
-    let failvolume = Volume::from_i32(-1);
-    assert!(failvolume.is_none());
-    let volume = Volume::from_i32(78);
-    assert!(volume.is_some());
-    let mut volume = volume.unwrap();
-    volume.add(200);
-    assert_eq!(Volume::from_i32(100).unwrap(), volume);
+/// Index used by PulseAudio for objects that do not exist, e.g. a sink without a card.
+pub const INVALID_INDEX: u32 = u32::MAX;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeviceMetadata {
+    pub icon_name: String,
+    pub form_factor: String,
+    pub bus: String,
+    /// For sinks the name of their monitor source,
+    /// for sources the name of the sink they monitor or empty if they are no monitor.
+    pub monitor_name: String,
+    pub base_volume: Volume,
+    pub hardware_volume: bool,
+    pub decibel_volume: bool,
+    /// Latency in microseconds.
+    pub latency: u64,
+    /// [`INVALID_INDEX`] if the device does not belong to a card.
+    pub card_index: u32,
+    /// Encodings the device accepts, passthrough capable devices support more than PCM.
+    pub formats: Vec<FormatEncoding>,
 }
 
-#[derive(Debug, Clone, Default)]
+impl Append for DeviceMetadata {
+    fn append_by_ref(&self, iter: &mut arg::IterAppend) {
+        iter.append_struct(|i| {
+            i.append(&self.icon_name);
+            i.append(&self.form_factor);
+            i.append(&self.bus);
+            i.append(&self.monitor_name);
+            i.append(self.base_volume);
+            i.append(self.hardware_volume);
+            i.append(self.decibel_volume);
+            i.append(self.latency);
+            i.append(self.card_index);
+            i.append(&self.formats);
+        });
+    }
+}
+
+impl<'a> Get<'a> for DeviceMetadata {
+    fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
+        let (
+            icon_name,
+            form_factor,
+            bus,
+            monitor_name,
+            base_volume,
+            hardware_volume,
+            decibel_volume,
+            latency,
+            card_index,
+            formats,
+        ) = <(
+            String,
+            String,
+            String,
+            String,
+            Volume,
+            bool,
+            bool,
+            u64,
+            u32,
+            Vec<FormatEncoding>,
+        )>::get(i)?;
+        Some(Self {
+            icon_name,
+            form_factor,
+            bus,
+            monitor_name,
+            base_volume,
+            hardware_volume,
+            decibel_volume,
+            latency,
+            card_index,
+            formats,
+        })
+    }
+}
+
+impl Arg for DeviceMetadata {
+    const ARG_TYPE: arg::ArgType = ArgType::Struct;
+    fn signature() -> Signature<'static> {
+        unsafe { Signature::from_slice_unchecked("(ssssubbtuai)\0") }
+    }
+}
+
+impl From<&SinkInfo<'_>> for DeviceMetadata {
+    fn from(value: &SinkInfo<'_>) -> Self {
+        let monitor_name = if let Some(monitor_opt) = &value.monitor_source_name {
+            String::from(monitor_opt.clone())
+        } else {
+            String::from("")
+        };
+        Self {
+            icon_name: value
+                .proplist
+                .get_str("device.icon_name")
+                .unwrap_or_default(),
+            form_factor: value
+                .proplist
+                .get_str("device.form_factor")
+                .unwrap_or_default(),
+            bus: value.proplist.get_str("device.bus").unwrap_or_default(),
+            monitor_name,
+            base_volume: value.base_volume.into(),
+            hardware_volume: value.flags.contains(SinkFlagSet::HW_VOLUME_CTRL),
+            decibel_volume: value.flags.contains(SinkFlagSet::DECIBEL_VOLUME),
+            latency: value.latency.0,
+            card_index: value.card.unwrap_or(INVALID_INDEX),
+            formats: value.formats.iter().map(FormatEncoding::from).collect(),
+        }
+    }
+}
+
+impl From<&SourceInfo<'_>> for DeviceMetadata {
+    fn from(value: &SourceInfo<'_>) -> Self {
+        let monitor_name = if let Some(monitor_opt) = &value.monitor_of_sink_name {
+            String::from(monitor_opt.clone())
+        } else {
+            String::from("")
+        };
+        Self {
+            icon_name: value
+                .proplist
+                .get_str("device.icon_name")
+                .unwrap_or_default(),
+            form_factor: value
+                .proplist
+                .get_str("device.form_factor")
+                .unwrap_or_default(),
+            bus: value.proplist.get_str("device.bus").unwrap_or_default(),
+            monitor_name,
+            base_volume: value.base_volume.into(),
+            hardware_volume: value.flags.contains(SourceFlagSet::HW_VOLUME_CTRL),
+            decibel_volume: value.flags.contains(SourceFlagSet::DECIBEL_VOLUME),
+            latency: value.latency.0,
+            card_index: value.card.unwrap_or(INVALID_INDEX),
+            formats: value.formats.iter().map(FormatEncoding::from).collect(),
+        }
+    }
+}
+
+/// State of a sink or source, values match the pulse sink and source states.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum DeviceState {
+    Invalid,
+    Running,
     #[default]
     Idle,
-    Invalid,
     Suspended,
-    Running,
 }
 
 impl Enum for DeviceState {
     fn from_i32(num: i32) -> Self {
         match num {
-            0 => DeviceState::Idle,
-            1 => DeviceState::Invalid,
+            0 => DeviceState::Running,
+            1 => DeviceState::Idle,
             2 => DeviceState::Suspended,
-            _ => DeviceState::Running,
+            _ => DeviceState::Invalid,
         }
     }
 
     fn to_i32(&self) -> i32 {
         match self {
-            DeviceState::Idle => 0,
-            DeviceState::Invalid => 1,
+            DeviceState::Invalid => -1,
+            DeviceState::Running => 0,
+            DeviceState::Idle => 1,
             DeviceState::Suspended => 2,
-            DeviceState::Running => 3,
         }
     }
 }
 
+impl From<pulse::def::SinkState> for DeviceState {
+    fn from(value: pulse::def::SinkState) -> Self {
+        DeviceState::from_i32(value as i32)
+    }
+}
+
+impl From<pulse::def::SourceState> for DeviceState {
+    fn from(value: pulse::def::SourceState) -> Self {
+        DeviceState::from_i32(value as i32)
+    }
+}
+
+impl Append for DeviceState {
+    fn append_by_ref(&self, iter: &mut arg::IterAppend) {
+        iter.append(self.to_i32());
+    }
+}
+
+impl<'a> Get<'a> for DeviceState {
+    fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
+        Some(DeviceState::from_i32(i32::get(i)?))
+    }
+}
+
+impl Arg for DeviceState {
+    const ARG_TYPE: arg::ArgType = ArgType::Int32;
+    fn signature() -> Signature<'static> {
+        unsafe { Signature::from_slice_unchecked("i\0") }
+    }
+}
+
+#[test]
+fn device_state_test() {
+    use pulse::def::{SinkState, SourceState};
+    assert_eq!(DeviceState::from(SinkState::Running), DeviceState::Running);
+    assert_eq!(DeviceState::from(SinkState::Idle), DeviceState::Idle);
+    assert_eq!(
+        DeviceState::from(SourceState::Suspended),
+        DeviceState::Suspended
+    );
+    assert_eq!(
+        DeviceState::from(SourceState::Invalid),
+        DeviceState::Invalid
+    );
+    assert_eq!(
+        DeviceState::from_i32(DeviceState::Suspended.to_i32()),
+        DeviceState::Suspended
+    );
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct Source {
     pub index: u32,
     pub name: String,
     pub alias: String,
     pub channels: u16,
-    pub volume: Vec<u32>,
+    pub channel_map: ChannelMap,
+    pub sample_spec: SampleSpec,
+    pub volume: ChannelVolumes,
     pub muted: bool,
-    pub active: i32,
+    pub active: DeviceState,
+    pub ports: Vec<Port>,
+    pub active_port: String,
+    pub metadata: DeviceMetadata,
 }
 
 unsafe impl Send for Source {}
@@ -134,25 +1567,60 @@ impl Append for Source {
             i.append(&self.name);
             i.append(&self.alias);
             i.append(self.channels);
+            i.append(&self.channel_map);
+            i.append(self.sample_spec);
             i.append(&self.volume);
             i.append(self.muted);
             i.append(self.active);
+            i.append(&self.ports);
+            i.append(&self.active_port);
+            i.append(&self.metadata);
         });
     }
 }
 
 impl<'a> Get<'a> for Source {
     fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
-        let (index, name, alias, channels, volume, muted, active) =
-            <(u32, String, String, u16, Vec<u32>, bool, i32)>::get(i)?;
+        let (
+            index,
+            name,
+            alias,
+            channels,
+            channel_map,
+            sample_spec,
+            volume,
+            muted,
+            active,
+            ports,
+            active_port,
+            metadata,
+        ) = <(
+            u32,
+            String,
+            String,
+            u16,
+            ChannelMap,
+            SampleSpec,
+            ChannelVolumes,
+            bool,
+            DeviceState,
+            Vec<Port>,
+            String,
+            DeviceMetadata,
+        )>::get(i)?;
         Some(Self {
             index,
             name,
             alias,
             channels,
+            channel_map,
+            sample_spec,
             volume,
             muted,
             active,
+            ports,
+            active_port,
+            metadata,
         })
     }
 }
@@ -160,7 +1628,7 @@ impl<'a> Get<'a> for Source {
 impl Arg for Source {
     const ARG_TYPE: arg::ArgType = ArgType::Struct;
     fn signature() -> Signature<'static> {
-        unsafe { Signature::from_slice_unchecked("(ussqaubi)\0") }
+        unsafe { Signature::from_slice_unchecked("(ussqai(iuy)aubia(ssui)s(ssssubbtuai))\0") }
     }
 }
 
@@ -177,22 +1645,39 @@ impl From<&SourceInfo<'_>> for Source {
             String::from("")
         };
         let channels = value.channel_map.len() as u16;
-        let mut volume = vec![0; channels as usize];
-        for i in 0..channels as usize {
-            unsafe { *volume.get_unchecked_mut(i) = value.volume.get()[i].0 };
-        }
+        let channel_map = ChannelMap::from(&value.channel_map);
+        let sample_spec = SampleSpec::from(&value.sample_spec);
+        let volume = ChannelVolumes::from(&value.volume);
+        let ports = value.ports.iter().map(Port::from).collect();
+        let active_port = if let Some(port_opt) = &value.active_port {
+            Port::from(port_opt.as_ref()).name
+        } else {
+            String::from("")
+        };
         Self {
             index: value.index,
             name,
             alias,
             channels,
+            channel_map,
+            sample_spec,
             volume,
             muted: value.mute,
-            active: value.state as i32,
+            active: value.state.into(),
+            ports,
+            active_port,
+            metadata: DeviceMetadata::from(value),
         }
     }
 }
 
+impl Source {
+    /// Monitor sources record the output of a sink and are usually hidden from users.
+    pub fn is_monitor(&self) -> bool {
+        !self.metadata.monitor_name.is_empty()
+    }
+}
+
 impl TAudioObject for Source {
     fn alias(&self) -> String {
         self.alias.clone()
@@ -202,7 +1687,7 @@ impl TAudioObject for Source {
         self.name.clone()
     }
 
-    fn volume(&self) -> Vec<u32> {
+    fn volume(&self) -> ChannelVolumes {
         self.volume.clone()
     }
 
@@ -214,6 +1699,14 @@ impl TAudioObject for Source {
         self.channels
     }
 
+    fn channel_map(&self) -> ChannelMap {
+        self.channel_map.clone()
+    }
+
+    fn sample_spec(&self) -> SampleSpec {
+        self.sample_spec
+    }
+
     fn muted(&self) -> bool {
         self.muted
     }
@@ -222,9 +1715,21 @@ impl TAudioObject for Source {
         self.muted = !self.muted;
     }
 
-    fn active(&self) -> i32 {
+    fn active(&self) -> DeviceState {
         self.active
     }
+
+    fn ports(&self) -> Vec<Port> {
+        self.ports.clone()
+    }
+
+    fn active_port(&self) -> String {
+        self.active_port.clone()
+    }
+
+    fn metadata_ref(&self) -> &DeviceMetadata {
+        &self.metadata
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -233,9 +1738,14 @@ pub struct Sink {
     pub name: String,
     pub alias: String,
     pub channels: u16,
-    pub volume: Vec<u32>,
+    pub channel_map: ChannelMap,
+    pub sample_spec: SampleSpec,
+    pub volume: ChannelVolumes,
     pub muted: bool,
-    pub active: i32,
+    pub active: DeviceState,
+    pub ports: Vec<Port>,
+    pub active_port: String,
+    pub metadata: DeviceMetadata,
 }
 
 unsafe impl Send for Sink {}
@@ -248,25 +1758,60 @@ impl Append for Sink {
             i.append(&self.name);
             i.append(&self.alias);
             i.append(self.channels);
+            i.append(&self.channel_map);
+            i.append(self.sample_spec);
             i.append(&self.volume);
             i.append(self.muted);
             i.append(self.active);
+            i.append(&self.ports);
+            i.append(&self.active_port);
+            i.append(&self.metadata);
         });
     }
 }
 
 impl<'a> Get<'a> for Sink {
     fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
-        let (index, name, alias, channels, volume, muted, active) =
-            <(u32, String, String, u16, Vec<u32>, bool, i32)>::get(i)?;
+        let (
+            index,
+            name,
+            alias,
+            channels,
+            channel_map,
+            sample_spec,
+            volume,
+            muted,
+            active,
+            ports,
+            active_port,
+            metadata,
+        ) = <(
+            u32,
+            String,
+            String,
+            u16,
+            ChannelMap,
+            SampleSpec,
+            ChannelVolumes,
+            bool,
+            DeviceState,
+            Vec<Port>,
+            String,
+            DeviceMetadata,
+        )>::get(i)?;
         Some(Self {
             index,
             name,
             alias,
             channels,
+            channel_map,
+            sample_spec,
             volume,
             muted,
             active,
+            ports,
+            active_port,
+            metadata,
         })
     }
 }
@@ -274,7 +1819,7 @@ impl<'a> Get<'a> for Sink {
 impl Arg for Sink {
     const ARG_TYPE: arg::ArgType = ArgType::Struct;
     fn signature() -> Signature<'static> {
-        unsafe { Signature::from_slice_unchecked("(ussqaubi)\0") }
+        unsafe { Signature::from_slice_unchecked("(ussqai(iuy)aubia(ssui)s(ssssubbtuai))\0") }
     }
 }
 
@@ -291,53 +1836,252 @@ impl From<&SinkInfo<'_>> for Sink {
             String::from("")
         };
         let channels = value.channel_map.len() as u16;
-        let mut volume = vec![0; channels as usize];
-        for i in 0..channels as usize {
-            unsafe { *volume.get_unchecked_mut(i) = value.volume.get()[i].0 };
-        }
+        let channel_map = ChannelMap::from(&value.channel_map);
+        let sample_spec = SampleSpec::from(&value.sample_spec);
+        let volume = ChannelVolumes::from(&value.volume);
+        let ports = value.ports.iter().map(Port::from).collect();
+        let active_port = if let Some(port_opt) = &value.active_port {
+            Port::from(port_opt.as_ref()).name
+        } else {
+            String::from("")
+        };
         Self {
             index: value.index,
             name,
             alias,
             channels,
+            channel_map,
+            sample_spec,
             volume,
             muted: value.mute,
-            active: value.state as i32,
+            active: value.state.into(),
+            ports,
+            active_port,
+            metadata: DeviceMetadata::from(value),
+        }
+    }
+}
+
+impl TAudioObject for Sink {
+    fn alias(&self) -> String {
+        self.alias.clone()
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn volume(&self) -> ChannelVolumes {
+        self.volume.clone()
+    }
+
+    fn index(&self) -> u32 {
+        self.index
+    }
+
+    fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    fn channel_map(&self) -> ChannelMap {
+        self.channel_map.clone()
+    }
+
+    fn sample_spec(&self) -> SampleSpec {
+        self.sample_spec
+    }
+
+    fn muted(&self) -> bool {
+        self.muted
+    }
+
+    fn toggle_muted(&mut self) {
+        self.muted = !self.muted;
+    }
+
+    fn active(&self) -> DeviceState {
+        self.active
+    }
+
+    fn ports(&self) -> Vec<Port> {
+        self.ports.clone()
+    }
+
+    fn active_port(&self) -> String {
+        self.active_port.clone()
+    }
+
+    fn metadata_ref(&self) -> &DeviceMetadata {
+        &self.metadata
+    }
+}
+
+/// Classification of a stream, used to hide streams that are not regular application playback
+/// or recording.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StreamKind {
+    #[default]
+    Application,
+    /// Short notification sounds with the media role "event".
+    Event,
+    /// Streams that only read peaks, e.g. volume meters of mixers.
+    PeakDetect,
+    /// Streams created by modules without a client, e.g. loopbacks.
+    Virtual,
+}
+
+impl Enum for StreamKind {
+    fn from_i32(num: i32) -> Self {
+        match num {
+            1 => StreamKind::Event,
+            2 => StreamKind::PeakDetect,
+            3 => StreamKind::Virtual,
+            _ => StreamKind::Application,
+        }
+    }
+
+    fn to_i32(&self) -> i32 {
+        match self {
+            StreamKind::Application => 0,
+            StreamKind::Event => 1,
+            StreamKind::PeakDetect => 2,
+            StreamKind::Virtual => 3,
         }
     }
 }
 
-impl TAudioObject for Sink {
-    fn alias(&self) -> String {
-        self.alias.clone()
+impl Append for StreamKind {
+    fn append_by_ref(&self, iter: &mut arg::IterAppend) {
+        iter.append(self.to_i32());
     }
+}
 
-    fn name(&self) -> String {
-        self.name.clone()
+impl<'a> Get<'a> for StreamKind {
+    fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
+        Some(StreamKind::from_i32(i32::get(i)?))
     }
+}
 
-    fn volume(&self) -> Vec<u32> {
-        self.volume.clone()
+impl Arg for StreamKind {
+    const ARG_TYPE: arg::ArgType = ArgType::Int32;
+    fn signature() -> Signature<'static> {
+        unsafe { Signature::from_slice_unchecked("i\0") }
     }
+}
 
-    fn index(&self) -> u32 {
-        self.index
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StreamMetadata {
+    pub icon_name: String,
+    /// 0 if the process id is unknown.
+    pub process_id: u32,
+    pub process_binary: String,
+    pub media_role: String,
+    pub media_name: String,
+    pub kind: StreamKind,
+    pub format: FormatEncoding,
+}
+
+impl StreamMetadata {
+    fn from_proplist(
+        proplist: &Proplist,
+        resample_method: &Option<Cow<'_, str>>,
+        client: Option<u32>,
+        format: &pulse::format::Info,
+    ) -> Self {
+        let media_role = proplist.get_str("media.role").unwrap_or_default();
+        let kind = if resample_method.as_deref() == Some("peaks") {
+            StreamKind::PeakDetect
+        } else if media_role == "event" {
+            StreamKind::Event
+        } else if client.is_none() {
+            StreamKind::Virtual
+        } else {
+            StreamKind::Application
+        };
+        let process_id = proplist
+            .get_str("application.process.id")
+            .and_then(|id| id.parse().ok())
+            .unwrap_or_default();
+        Self {
+            icon_name: proplist
+                .get_str("application.icon_name")
+                .unwrap_or_default(),
+            process_id,
+            process_binary: proplist
+                .get_str("application.process.binary")
+                .unwrap_or_default(),
+            media_role,
+            media_name: proplist.get_str("media.name").unwrap_or_default(),
+            kind,
+            format: FormatEncoding::from(format),
+        }
     }
+}
 
-    fn channels(&self) -> u16 {
-        self.channels
+impl Append for StreamMetadata {
+    fn append_by_ref(&self, iter: &mut arg::IterAppend) {
+        iter.append_struct(|i| {
+            i.append(&self.icon_name);
+            i.append(self.process_id);
+            i.append(&self.process_binary);
+            i.append(&self.media_role);
+            i.append(&self.media_name);
+            i.append(self.kind);
+            i.append(self.format);
+        });
     }
+}
 
-    fn muted(&self) -> bool {
-        self.muted
+impl<'a> Get<'a> for StreamMetadata {
+    fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
+        let (icon_name, process_id, process_binary, media_role, media_name, kind, format) =
+            <(
+                String,
+                u32,
+                String,
+                String,
+                String,
+                StreamKind,
+                FormatEncoding,
+            )>::get(i)?;
+        Some(Self {
+            icon_name,
+            process_id,
+            process_binary,
+            media_role,
+            media_name,
+            kind,
+            format,
+        })
     }
+}
 
-    fn toggle_muted(&mut self) {
-        self.muted = !self.muted;
+impl Arg for StreamMetadata {
+    const ARG_TYPE: arg::ArgType = ArgType::Struct;
+    fn signature() -> Signature<'static> {
+        unsafe { Signature::from_slice_unchecked("(susssii)\0") }
     }
+}
 
-    fn active(&self) -> i32 {
-        self.active
+impl From<&SinkInputInfo<'_>> for StreamMetadata {
+    fn from(value: &SinkInputInfo<'_>) -> Self {
+        StreamMetadata::from_proplist(
+            &value.proplist,
+            &value.resample_method,
+            value.client,
+            &value.format,
+        )
+    }
+}
+
+impl From<&SourceOutputInfo<'_>> for StreamMetadata {
+    fn from(value: &SourceOutputInfo<'_>) -> Self {
+        StreamMetadata::from_proplist(
+            &value.proplist,
+            &value.resample_method,
+            value.client,
+            &value.format,
+        )
     }
 }
 
@@ -348,9 +2092,12 @@ pub struct InputStream {
     pub application_name: String,
     pub sink_index: u32,
     pub channels: u16,
-    pub volume: Vec<u32>,
+    pub channel_map: ChannelMap,
+    pub sample_spec: SampleSpec,
+    pub volume: ChannelVolumes,
     pub muted: bool,
     pub corked: bool,
+    pub metadata: StreamMetadata,
 }
 
 impl Append for InputStream {
@@ -361,26 +2108,55 @@ impl Append for InputStream {
             i.append(&self.application_name);
             i.append(self.sink_index);
             i.append(self.channels);
+            i.append(&self.channel_map);
+            i.append(self.sample_spec);
             i.append(&self.volume);
             i.append(self.muted);
             i.append(self.corked);
+            i.append(&self.metadata);
         });
     }
 }
 
 impl<'a> Get<'a> for InputStream {
     fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
-        let (index, name, application_name, sink_index, channels, volume, muted, corked) =
-            <(u32, String, String, u32, u16, Vec<u32>, bool, bool)>::get(i)?;
+        let (
+            index,
+            name,
+            application_name,
+            sink_index,
+            channels,
+            channel_map,
+            sample_spec,
+            volume,
+            muted,
+            corked,
+            metadata,
+        ) = <(
+            u32,
+            String,
+            String,
+            u32,
+            u16,
+            ChannelMap,
+            SampleSpec,
+            ChannelVolumes,
+            bool,
+            bool,
+            StreamMetadata,
+        )>::get(i)?;
         Some(Self {
             index,
             name,
             application_name,
             sink_index,
             channels,
+            channel_map,
+            sample_spec,
             volume,
             muted,
             corked,
+            metadata,
         })
     }
 }
@@ -388,7 +2164,7 @@ impl<'a> Get<'a> for InputStream {
 impl Arg for InputStream {
     const ARG_TYPE: arg::ArgType = ArgType::Struct;
     fn signature() -> Signature<'static> {
-        unsafe { Signature::from_slice_unchecked("(ussuqaubb)\0") }
+        unsafe { Signature::from_slice_unchecked("(ussuqai(iuy)aubb(susssii))\0") }
     }
 }
 
@@ -404,19 +2180,21 @@ impl From<&SinkInputInfo<'_>> for InputStream {
             .get_str("application.name")
             .unwrap_or_default();
         let channels = value.channel_map.len() as u16;
-        let mut volume = vec![0; channels as usize];
-        for i in 0..channels as usize {
-            unsafe { *volume.get_unchecked_mut(i) = value.volume.get()[i].0 };
-        }
+        let channel_map = ChannelMap::from(&value.channel_map);
+        let sample_spec = SampleSpec::from(&value.sample_spec);
+        let volume = ChannelVolumes::from(&value.volume);
         Self {
             index: value.index,
             name,
             application_name,
             sink_index: value.sink,
             channels,
+            channel_map,
+            sample_spec,
             volume,
             muted: value.mute,
             corked: value.corked,
+            metadata: StreamMetadata::from(value),
         }
     }
 }
@@ -435,14 +2213,22 @@ impl TAudioStreamObject for InputStream {
     }
 
     fn audio_object_index(&self) -> u32 {
-        self.index
+        self.sink_index
     }
 
     fn channels(&self) -> u16 {
         self.channels
     }
 
-    fn volume(&self) -> Vec<u32> {
+    fn channel_map(&self) -> ChannelMap {
+        self.channel_map.clone()
+    }
+
+    fn sample_spec(&self) -> SampleSpec {
+        self.sample_spec
+    }
+
+    fn volume(&self) -> ChannelVolumes {
         self.volume.clone()
     }
 
@@ -457,6 +2243,37 @@ impl TAudioStreamObject for InputStream {
     fn corked(&self) -> bool {
         self.corked
     }
+
+    fn metadata_ref(&self) -> &StreamMetadata {
+        &self.metadata
+    }
+}
+
+#[test]
+fn input_stream_dbus_test() {
+    let stream = InputStream {
+        application_name: String::from("Firefox"),
+        metadata: StreamMetadata {
+            icon_name: String::from("firefox"),
+            process_id: 1234,
+            process_binary: String::from("firefox"),
+            media_role: String::from("event"),
+            media_name: String::from("Notification"),
+            kind: StreamKind::Event,
+            format: FormatEncoding::Pcm,
+        },
+        ..Default::default()
+    };
+    let message = dbus::Message::new_signal("/test", "org.test", "Test")
+        .unwrap()
+        .append1(&stream);
+    assert_eq!(
+        message.iter_init().signature().to_string(),
+        "(ussuqai(iuy)aubb(susssii))"
+    );
+    let received: InputStream = message.read1().unwrap();
+    assert_eq!(received.metadata, stream.metadata);
+    assert_eq!(received.process_id(), Some(1234));
 }
 
 #[derive(Debug, Clone, Default)]
@@ -466,9 +2283,12 @@ pub struct OutputStream {
     pub application_name: String,
     pub source_index: u32,
     pub channels: u16,
-    pub volume: Vec<u32>,
+    pub channel_map: ChannelMap,
+    pub sample_spec: SampleSpec,
+    pub volume: ChannelVolumes,
     pub muted: bool,
     pub corked: bool,
+    pub metadata: StreamMetadata,
 }
 
 impl Append for OutputStream {
@@ -479,26 +2299,55 @@ impl Append for OutputStream {
             i.append(&self.application_name);
             i.append(self.source_index);
             i.append(self.channels);
+            i.append(&self.channel_map);
+            i.append(self.sample_spec);
             i.append(&self.volume);
             i.append(self.muted);
             i.append(self.corked);
+            i.append(&self.metadata);
         });
     }
 }
 
 impl<'a> Get<'a> for OutputStream {
     fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
-        let (index, name, application_name, source_index, channels, volume, muted, corked) =
-            <(u32, String, String, u32, u16, Vec<u32>, bool, bool)>::get(i)?;
+        let (
+            index,
+            name,
+            application_name,
+            source_index,
+            channels,
+            channel_map,
+            sample_spec,
+            volume,
+            muted,
+            corked,
+            metadata,
+        ) = <(
+            u32,
+            String,
+            String,
+            u32,
+            u16,
+            ChannelMap,
+            SampleSpec,
+            ChannelVolumes,
+            bool,
+            bool,
+            StreamMetadata,
+        )>::get(i)?;
         Some(Self {
             index,
             name,
             application_name,
             source_index,
             channels,
+            channel_map,
+            sample_spec,
             volume,
             muted,
             corked,
+            metadata,
         })
     }
 }
@@ -506,7 +2355,7 @@ impl<'a> Get<'a> for OutputStream {
 impl Arg for OutputStream {
     const ARG_TYPE: arg::ArgType = ArgType::Struct;
     fn signature() -> Signature<'static> {
-        unsafe { Signature::from_slice_unchecked("(ussuqaubb)\0") }
+        unsafe { Signature::from_slice_unchecked("(ussuqai(iuy)aubb(susssii))\0") }
     }
 }
 
@@ -522,19 +2371,21 @@ impl From<&SourceOutputInfo<'_>> for OutputStream {
             .get_str("application.name")
             .unwrap_or_default();
         let channels = value.channel_map.len() as u16;
-        let mut volume = vec![0; channels as usize];
-        for i in 0..channels as usize {
-            unsafe { *volume.get_unchecked_mut(i) = value.volume.get()[i].0 };
-        }
+        let channel_map = ChannelMap::from(&value.channel_map);
+        let sample_spec = SampleSpec::from(&value.sample_spec);
+        let volume = ChannelVolumes::from(&value.volume);
         Self {
             index: value.index,
             name,
             application_name,
             source_index: value.source,
             channels,
+            channel_map,
+            sample_spec,
             volume,
             muted: value.mute,
             corked: value.corked,
+            metadata: StreamMetadata::from(value),
         }
     }
 }
@@ -553,14 +2404,22 @@ impl TAudioStreamObject for OutputStream {
     }
 
     fn audio_object_index(&self) -> u32 {
-        self.index
+        self.source_index
     }
 
     fn channels(&self) -> u16 {
         self.channels
     }
 
-    fn volume(&self) -> Vec<u32> {
+    fn channel_map(&self) -> ChannelMap {
+        self.channel_map.clone()
+    }
+
+    fn sample_spec(&self) -> SampleSpec {
+        self.sample_spec
+    }
+
+    fn volume(&self) -> ChannelVolumes {
         self.volume.clone()
     }
 
@@ -575,14 +2434,21 @@ impl TAudioStreamObject for OutputStream {
     fn corked(&self) -> bool {
         self.corked
     }
+
+    fn metadata_ref(&self) -> &StreamMetadata {
+        &self.metadata
+    }
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct Card {
     pub index: u32,
     pub name: String,
+    /// Stable pulse name of the card, e.g. `alsa_card.pci-0000_00_1f.3`.
+    pub system_name: String,
     pub profiles: Vec<CardProfile>,
     pub active_profile: String,
+    pub ports: Vec<CardPort>,
 }
 
 impl Append for Card {
@@ -590,21 +2456,25 @@ impl Append for Card {
         iter.append_struct(|i| {
             i.append(self.index);
             i.append(&self.name);
+            i.append(&self.system_name);
             i.append(&self.profiles);
             i.append(&self.active_profile);
+            i.append(&self.ports);
         });
     }
 }
 
 impl<'a> Get<'a> for Card {
     fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
-        let (index, name, profiles, active_profile) =
-            <(u32, String, Vec<CardProfile>, String)>::get(i)?;
+        let (index, name, system_name, profiles, active_profile, ports) =
+            <(u32, String, String, Vec<CardProfile>, String, Vec<CardPort>)>::get(i)?;
         Some(Self {
             index,
             name,
+            system_name,
             profiles,
             active_profile,
+            ports,
         })
     }
 }
@@ -612,41 +2482,81 @@ impl<'a> Get<'a> for Card {
 impl Arg for Card {
     const ARG_TYPE: arg::ArgType = ArgType::Struct;
     fn signature() -> Signature<'static> {
-        unsafe { Signature::from_slice_unchecked("(usa(ssb)s)\0") }
+        unsafe { Signature::from_slice_unchecked("(ussa(ssuuui)sa(ssuiasx))\0") }
     }
 }
 
 impl From<&CardInfo<'_>> for Card {
     fn from(value: &CardInfo<'_>) -> Self {
-        let name = if let Some(name_opt) = &value.proplist.get_str("alsa.card_name") {
-            name_opt.clone()
+        let name = if let Some(name_opt) = value.proplist.get_str("alsa.card_name") {
+            name_opt
+        } else if let Some(name_opt) = value.proplist.get_str("device.description") {
+            name_opt
+        } else if let Some(name_opt) = value.proplist.get_str("device.product.name") {
+            name_opt
         } else {
             String::from("Unnamed")
         };
+        let system_name = if let Some(name_opt) = &value.name {
+            String::from(name_opt.clone())
+        } else {
+            String::from("")
+        };
         let index = value.index;
         let mut profiles = Vec::new();
         for profile in value.profiles.iter() {
             profiles.push(CardProfile::from(profile));
         }
         let active_profile = if let Some(profile_opt) = value.active_profile.as_ref() {
-            profile_opt.name.clone().unwrap().to_string()
+            if let Some(name_opt) = &profile_opt.name {
+                String::from(name_opt.clone())
+            } else {
+                String::from("Off")
+            }
         } else {
             String::from("Off")
         };
+        let ports = value.ports.iter().map(CardPort::from).collect();
         Self {
             index,
             name,
+            system_name,
             profiles,
             active_profile,
+            ports,
         }
     }
 }
 
+impl Card {
+    /// Latency offset of `port` in microseconds.
+    pub fn port_latency_offset(&self, port: &str) -> Option<i64> {
+        self.ports
+            .iter()
+            .find(|card_port| card_port.name == port)
+            .map(|card_port| card_port.latency_offset)
+    }
+}
+
+/// Latency offset of the active port of a sink or source in microseconds,
+/// 0 if the device has no card.
+pub fn device_latency_offset(device: &impl TAudioObject, cards: &[Card]) -> i64 {
+    device
+        .card_index()
+        .and_then(|index| cards.iter().find(|card| card.index == index))
+        .and_then(|card| card.port_latency_offset(&device.active_port()))
+        .unwrap_or_default()
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct CardProfile {
     pub name: String,
     pub description: String,
-    pub available: bool,
+    /// The higher this value is, the more useful this profile is as a default.
+    pub priority: u32,
+    pub sink_count: u32,
+    pub source_count: u32,
+    pub available: Availability,
 }
 
 impl Append for CardProfile {
@@ -654,6 +2564,9 @@ impl Append for CardProfile {
         iter.append_struct(|i| {
             i.append(&self.name);
             i.append(&self.description);
+            i.append(self.priority);
+            i.append(self.sink_count);
+            i.append(self.source_count);
             i.append(self.available);
         });
     }
@@ -661,10 +2574,14 @@ impl Append for CardProfile {
 
 impl<'a> Get<'a> for CardProfile {
     fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
-        let (name, description, available) = <(String, String, bool)>::get(i)?;
+        let (name, description, priority, sink_count, source_count, available) =
+            <(String, String, u32, u32, u32, Availability)>::get(i)?;
         Some(Self {
             name,
             description,
+            priority,
+            sink_count,
+            source_count,
             available,
         })
     }
@@ -673,7 +2590,7 @@ impl<'a> Get<'a> for CardProfile {
 impl Arg for CardProfile {
     const ARG_TYPE: arg::ArgType = ArgType::Struct;
     fn signature() -> Signature<'static> {
-        unsafe { Signature::from_slice_unchecked("(ssb)\0") }
+        unsafe { Signature::from_slice_unchecked("(ssuuui)\0") }
     }
 }
 
@@ -689,10 +2606,376 @@ impl From<&CardProfileInfo<'_>> for CardProfile {
         } else {
             String::from("")
         };
+        let available = if value.available {
+            Availability::Yes
+        } else {
+            Availability::No
+        };
+        Self {
+            name,
+            description,
+            priority: value.priority,
+            sink_count: value.n_sinks,
+            source_count: value.n_sources,
+            available,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CardPort {
+    pub name: String,
+    pub description: String,
+    pub priority: u32,
+    pub available: Availability,
+    pub profiles: Vec<String>,
+    /// Latency offset in microseconds, used to compensate for e.g. Bluetooth delays.
+    pub latency_offset: i64,
+}
+
+impl Append for CardPort {
+    fn append_by_ref(&self, iter: &mut arg::IterAppend) {
+        iter.append_struct(|i| {
+            i.append(&self.name);
+            i.append(&self.description);
+            i.append(self.priority);
+            i.append(self.available);
+            i.append(&self.profiles);
+            i.append(self.latency_offset);
+        });
+    }
+}
+
+impl<'a> Get<'a> for CardPort {
+    fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
+        let (name, description, priority, available, profiles, latency_offset) =
+            <(String, String, u32, Availability, Vec<String>, i64)>::get(i)?;
+        Some(Self {
+            name,
+            description,
+            priority,
+            available,
+            profiles,
+            latency_offset,
+        })
+    }
+}
+
+impl Arg for CardPort {
+    const ARG_TYPE: arg::ArgType = ArgType::Struct;
+    fn signature() -> Signature<'static> {
+        unsafe { Signature::from_slice_unchecked("(ssuiasx)\0") }
+    }
+}
+
+impl From<&CardPortInfo<'_>> for CardPort {
+    fn from(value: &CardPortInfo<'_>) -> Self {
+        let name = if let Some(name_opt) = &value.name {
+            String::from(name_opt.clone())
+        } else {
+            String::from("")
+        };
+        let description = if let Some(description_opt) = &value.description {
+            String::from(description_opt.clone())
+        } else {
+            String::from("")
+        };
+        let profiles = value
+            .profiles
+            .iter()
+            .filter_map(|profile| profile.name.as_ref())
+            .map(|profile| String::from(profile.clone()))
+            .collect();
         Self {
             name,
             description,
-            available: value.available,
+            priority: value.priority,
+            available: value.available.into(),
+            profiles,
+            latency_offset: value.latency_offset,
+        }
+    }
+}
+
+impl CardPort {
+    pub fn supports_profile(&self, profile: &str) -> bool {
+        self.profiles.iter().any(|name| name == profile)
+    }
+}
+
+/// Request to change the latency offset of a card port, the offset is in microseconds.
+/// Pulse identifies the card by name, use [`Card::system_name`] and not the display name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LatencyOffset {
+    pub card_name: String,
+    pub port: String,
+    pub offset: i64,
+}
+
+impl LatencyOffset {
+    /// Offset for the active port of a sink or source, None if it does not belong to a card.
+    pub fn for_device(device: &impl TAudioObject, cards: &[Card], offset: i64) -> Option<Self> {
+        let card_index = device.card_index()?;
+        let card = cards.iter().find(|card| card.index == card_index)?;
+        Some(Self {
+            card_name: card.system_name.clone(),
+            port: device.active_port(),
+            offset,
+        })
+    }
+}
+
+impl Append for LatencyOffset {
+    fn append_by_ref(&self, iter: &mut arg::IterAppend) {
+        iter.append_struct(|i| {
+            i.append(&self.card_name);
+            i.append(&self.port);
+            i.append(self.offset);
+        });
+    }
+}
+
+impl<'a> Get<'a> for LatencyOffset {
+    fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
+        let (card_name, port, offset) = <(String, String, i64)>::get(i)?;
+        Some(Self {
+            card_name,
+            port,
+            offset,
+        })
+    }
+}
+
+impl Arg for LatencyOffset {
+    const ARG_TYPE: arg::ArgType = ArgType::Struct;
+    fn signature() -> Signature<'static> {
+        unsafe { Signature::from_slice_unchecked("(ssx)\0") }
+    }
+}
+
+#[test]
+fn card_dbus_test() {
+    let card = Card {
+        index: 1,
+        name: String::from("Headset"),
+        system_name: String::from("bluez_card.00_11_22_33_44_55"),
+        profiles: vec![CardProfile {
+            name: String::from("a2dp-sink"),
+            description: String::from("High Fidelity Playback (A2DP Sink)"),
+            priority: 40,
+            sink_count: 1,
+            source_count: 0,
+            available: Availability::Yes,
+        }],
+        active_profile: String::from("a2dp-sink"),
+        ports: vec![CardPort {
+            name: String::from("headset-output"),
+            description: String::from("Headset"),
+            priority: 0,
+            available: Availability::Unknown,
+            profiles: vec![String::from("a2dp-sink")],
+            latency_offset: 150000,
+        }],
+    };
+    let message = dbus::Message::new_signal("/test", "org.test", "Test")
+        .unwrap()
+        .append1(&card);
+    assert_eq!(
+        message.iter_init().signature().to_string(),
+        "(ussa(ssuuui)sa(ssuiasx))"
+    );
+    let received: Card = message.read1().unwrap();
+    assert_eq!(received.profiles[0].priority, 40);
+    assert!(received.ports[0].supports_profile("a2dp-sink"));
+    assert_eq!(received.port_latency_offset("headset-output"), Some(150000));
+
+    let mut sink = Sink {
+        active_port: String::from("headset-output"),
+        ..Default::default()
+    };
+    sink.metadata.card_index = 1;
+    assert_eq!(
+        device_latency_offset(&sink, std::slice::from_ref(&received)),
+        150000
+    );
+    let offset = LatencyOffset::for_device(&sink, &[received], -20000).unwrap();
+    assert_eq!(offset.card_name, "bluez_card.00_11_22_33_44_55");
+    let message = dbus::Message::new_signal("/test", "org.test", "Test")
+        .unwrap()
+        .append1(&offset);
+    assert_eq!(message.iter_init().signature().to_string(), "(ssx)");
+    assert_eq!(message.read1::<LatencyOffset>().unwrap(), offset);
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ServerInfo {
+    pub server_name: String,
+    pub server_version: String,
+    pub default_sink: String,
+    pub default_source: String,
+    pub sample_spec: SampleSpec,
+}
+
+impl Append for ServerInfo {
+    fn append_by_ref(&self, iter: &mut arg::IterAppend) {
+        iter.append_struct(|i| {
+            i.append(&self.server_name);
+            i.append(&self.server_version);
+            i.append(&self.default_sink);
+            i.append(&self.default_source);
+            i.append(self.sample_spec);
+        });
+    }
+}
+
+impl<'a> Get<'a> for ServerInfo {
+    fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
+        let (server_name, server_version, default_sink, default_source, sample_spec) =
+            <(String, String, String, String, SampleSpec)>::get(i)?;
+        Some(Self {
+            server_name,
+            server_version,
+            default_sink,
+            default_source,
+            sample_spec,
+        })
+    }
+}
+
+impl Arg for ServerInfo {
+    const ARG_TYPE: arg::ArgType = ArgType::Struct;
+    fn signature() -> Signature<'static> {
+        unsafe { Signature::from_slice_unchecked("(ssss(iuy))\0") }
+    }
+}
+
+impl From<&PulseServerInfo<'_>> for ServerInfo {
+    fn from(value: &PulseServerInfo<'_>) -> Self {
+        let server_name = if let Some(name_opt) = &value.server_name {
+            String::from(name_opt.clone())
+        } else {
+            String::from("")
+        };
+        let server_version = if let Some(version_opt) = &value.server_version {
+            String::from(version_opt.clone())
+        } else {
+            String::from("")
+        };
+        let default_sink = if let Some(sink_opt) = &value.default_sink_name {
+            String::from(sink_opt.clone())
+        } else {
+            String::from("")
+        };
+        let default_source = if let Some(source_opt) = &value.default_source_name {
+            String::from(source_opt.clone())
+        } else {
+            String::from("")
+        };
+        Self {
+            server_name,
+            server_version,
+            default_sink,
+            default_source,
+            sample_spec: SampleSpec::from(&value.sample_spec),
+        }
+    }
+}
+
+#[test]
+fn server_info_dbus_test() {
+    let info = ServerInfo {
+        server_name: String::from("PulseAudio (on PipeWire 1.0.5)"),
+        server_version: String::from("15.0.0"),
+        default_sink: String::from("alsa_output.usb-headset"),
+        default_source: String::from("alsa_input.usb-headset"),
+        sample_spec: SampleSpec {
+            format: SampleFormat::S24le,
+            rate: 48000,
+            channels: 2,
+        },
+    };
+    let message = dbus::Message::new_signal("/test", "org.test", "Test")
+        .unwrap()
+        .append1(&info);
+    assert_eq!(message.iter_init().signature().to_string(), "(ssss(iuy))");
+    let received: ServerInfo = message.read1().unwrap();
+    assert_eq!(received, info);
+    assert_eq!(received.sample_spec.to_string(), "s24le 2ch 48000Hz");
+    assert_eq!(received.sample_spec.format.bits(), 24);
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Module {
+    pub index: u32,
+    pub name: String,
+    pub argument: String,
+    /// [`INVALID_INDEX`] if the module does not track its usage.
+    pub usage_count: u32,
+}
+
+impl Append for Module {
+    fn append_by_ref(&self, iter: &mut arg::IterAppend) {
+        iter.append_struct(|i| {
+            i.append(self.index);
+            i.append(&self.name);
+            i.append(&self.argument);
+            i.append(self.usage_count);
+        });
+    }
+}
+
+impl<'a> Get<'a> for Module {
+    fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
+        let (index, name, argument, usage_count) = <(u32, String, String, u32)>::get(i)?;
+        Some(Self {
+            index,
+            name,
+            argument,
+            usage_count,
+        })
+    }
+}
+
+impl Arg for Module {
+    const ARG_TYPE: arg::ArgType = ArgType::Struct;
+    fn signature() -> Signature<'static> {
+        unsafe { Signature::from_slice_unchecked("(ussu)\0") }
+    }
+}
+
+impl From<&ModuleInfo<'_>> for Module {
+    fn from(value: &ModuleInfo<'_>) -> Self {
+        let name = if let Some(name_opt) = &value.name {
+            String::from(name_opt.clone())
+        } else {
+            String::from("")
+        };
+        let argument = if let Some(argument_opt) = &value.argument {
+            String::from(argument_opt.clone())
+        } else {
+            String::from("")
+        };
+        Self {
+            index: value.index,
+            name,
+            argument,
+            usage_count: value.n_used.unwrap_or(INVALID_INDEX),
         }
     }
 }
+
+#[test]
+fn module_dbus_test() {
+    let module = Module {
+        index: 23,
+        name: String::from("module-null-sink"),
+        argument: String::from("sink_name=reset_null"),
+        usage_count: INVALID_INDEX,
+    };
+    let message = dbus::Message::new_signal("/test", "org.test", "Test")
+        .unwrap()
+        .append1(&module);
+    assert_eq!(message.iter_init().signature().to_string(), "(ussu)");
+    let received: Module = message.read1().unwrap();
+    assert_eq!(received, module);
+}
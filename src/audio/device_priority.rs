@@ -0,0 +1,289 @@
+use toml::{Table, Value};
+
+use crate::signals::{SinkAdded, SinkRemoved, SourceAdded, SourceRemoved};
+use crate::utils::config::{parse_config, write_config_values};
+
+use super::audio_structures::{Sink, Source, TAudioObject};
+
+pub const DEVICE_PRIORITY_CATEGORY: &str = "DevicePriority";
+
+/// Ordered lists of preferred sinks and sources together with the auto-switch policy.
+/// Entries are device names, `*` matches any number of characters.
+///
+/// ```toml
+/// [DevicePriority]
+/// auto_switch = true
+/// sinks = ["alsa_output.usb-*", "alsa_output.pci-*"]
+/// sources = ["alsa_input.usb-*"]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DevicePriority {
+    pub auto_switch: bool,
+    pub sinks: Vec<String>,
+    pub sources: Vec<String>,
+}
+
+impl DevicePriority {
+    pub fn load() -> Self {
+        Self::from_toml(&parse_config())
+    }
+
+    pub fn from_toml(config: &Table) -> Self {
+        let Some(Value::Table(category)) = config.get(DEVICE_PRIORITY_CATEGORY) else {
+            return Self::default();
+        };
+        let patterns = |key: &str| -> Vec<String> {
+            if let Some(Value::Array(patterns)) = category.get(key) {
+                patterns
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(String::from)
+                    .collect()
+            } else {
+                Vec::new()
+            }
+        };
+        Self {
+            auto_switch: category
+                .get("auto_switch")
+                .and_then(Value::as_bool)
+                .unwrap_or_default(),
+            sinks: patterns("sinks"),
+            sources: patterns("sources"),
+        }
+    }
+
+    pub fn save(&self) -> bool {
+        write_config_values(DEVICE_PRIORITY_CATEGORY, self.to_toml())
+    }
+
+    pub fn to_toml(&self) -> Table {
+        let patterns = |patterns: &[String]| {
+            Value::Array(patterns.iter().cloned().map(Value::String).collect())
+        };
+        let mut table = Table::new();
+        table.insert("auto_switch".into(), Value::Boolean(self.auto_switch));
+        table.insert("sinks".into(), patterns(&self.sinks));
+        table.insert("sources".into(), patterns(&self.sources));
+        table
+    }
+
+    pub fn preferred_sink(&self, sinks: &[Sink]) -> Option<String> {
+        preferred(&self.sinks, sinks.iter())
+    }
+
+    /// Monitors are never preferred.
+    pub fn preferred_source(&self, sources: &[Source]) -> Option<String> {
+        preferred(
+            &self.sources,
+            sources.iter().filter(|source| !source.is_monitor()),
+        )
+    }
+
+    /// New default sink after `event`, None if the default should stay.
+    /// Only switches when the added sink has the highest priority, so a manually chosen
+    /// default is not replaced by unrelated devices.
+    pub fn sink_added(
+        &self,
+        event: &SinkAdded,
+        sinks: &[Sink],
+        default_sink: &str,
+    ) -> Option<String> {
+        if !self.auto_switch || event.sink.name == default_sink {
+            return None;
+        }
+        let mut sinks = sinks.to_vec();
+        if !sinks.iter().any(|sink| sink.index == event.sink.index) {
+            sinks.push(event.sink.clone());
+        }
+        self.preferred_sink(&sinks)
+            .filter(|preferred| *preferred == event.sink.name)
+    }
+
+    /// New default sink after `event`, None if the removed sink was not the default.
+    /// Falls back to the first remaining sink if no sink is in the priority list.
+    pub fn sink_removed(
+        &self,
+        event: &SinkRemoved,
+        sinks: &[Sink],
+        default_sink: &str,
+    ) -> Option<String> {
+        if !self.auto_switch {
+            return None;
+        }
+        let remaining: Vec<Sink> = sinks
+            .iter()
+            .filter(|sink| sink.index != event.index)
+            .cloned()
+            .collect();
+        if remaining.iter().any(|sink| sink.name == default_sink) {
+            return None;
+        }
+        self.preferred_sink(&remaining)
+            .or_else(|| remaining.first().map(|sink| sink.name.clone()))
+    }
+
+    /// Same as [`DevicePriority::sink_added`] for sources, monitors are ignored.
+    pub fn source_added(
+        &self,
+        event: &SourceAdded,
+        sources: &[Source],
+        default_source: &str,
+    ) -> Option<String> {
+        if !self.auto_switch || event.source.name == default_source || event.source.is_monitor() {
+            return None;
+        }
+        let mut sources = sources.to_vec();
+        if !sources
+            .iter()
+            .any(|source| source.index == event.source.index)
+        {
+            sources.push(event.source.clone());
+        }
+        self.preferred_source(&sources)
+            .filter(|preferred| *preferred == event.source.name)
+    }
+
+    /// Same as [`DevicePriority::sink_removed`] for sources, monitors are only used if
+    /// nothing else is left.
+    pub fn source_removed(
+        &self,
+        event: &SourceRemoved,
+        sources: &[Source],
+        default_source: &str,
+    ) -> Option<String> {
+        if !self.auto_switch {
+            return None;
+        }
+        let remaining: Vec<Source> = sources
+            .iter()
+            .filter(|source| source.index != event.index)
+            .cloned()
+            .collect();
+        if remaining.iter().any(|source| source.name == default_source) {
+            return None;
+        }
+        self.preferred_source(&remaining)
+            .or_else(|| {
+                remaining
+                    .iter()
+                    .find(|source| !source.is_monitor())
+                    .map(|source| source.name.clone())
+            })
+            .or_else(|| remaining.first().map(|source| source.name.clone()))
+    }
+}
+
+fn preferred<'a, T: TAudioObject + 'a>(
+    patterns: &[String],
+    devices: impl Iterator<Item = &'a T> + Clone,
+) -> Option<String> {
+    patterns.iter().find_map(|pattern| {
+        devices
+            .clone()
+            .find(|device| matches_pattern(pattern, &device.name()))
+            .map(|device| device.name())
+    })
+}
+
+pub fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // no wildcard
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(position) => rest = &rest[position + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+#[test]
+fn device_pattern_test() {
+    assert!(matches_pattern(
+        "alsa_output.usb-*",
+        "alsa_output.usb-headset"
+    ));
+    assert!(matches_pattern(
+        "*headset*",
+        "alsa_output.usb-headset.analog"
+    ));
+    assert!(matches_pattern("speakers", "speakers"));
+    assert!(!matches_pattern("speakers", "speakers.2"));
+    assert!(!matches_pattern("alsa_*.usb-*", "alsa_output.pci-speakers"));
+    assert!(matches_pattern("*", ""));
+}
+
+#[test]
+fn device_priority_test() {
+    let config = r#"
+        [DevicePriority]
+        auto_switch = true
+        sinks = ["alsa_output.usb-*", "alsa_output.pci-*"]
+    "#
+    .parse::<Table>()
+    .unwrap();
+    let priority = DevicePriority::from_toml(&config);
+    let mut saved = Table::new();
+    saved.insert(
+        DEVICE_PRIORITY_CATEGORY.into(),
+        Value::Table(priority.to_toml()),
+    );
+    assert_eq!(DevicePriority::from_toml(&saved), priority);
+    let speakers = Sink {
+        index: 1,
+        name: String::from("alsa_output.pci-speakers"),
+        ..Default::default()
+    };
+    let headset = Sink {
+        index: 2,
+        name: String::from("alsa_output.usb-headset"),
+        ..Default::default()
+    };
+    let hdmi = Sink {
+        index: 3,
+        name: String::from("alsa_output.hdmi"),
+        ..Default::default()
+    };
+
+    let event = SinkAdded {
+        sink: headset.clone(),
+    };
+    assert_eq!(
+        priority.sink_added(&event, std::slice::from_ref(&speakers), &speakers.name),
+        Some(headset.name.clone())
+    );
+    let event = SinkAdded { sink: hdmi.clone() };
+    assert_eq!(
+        priority.sink_added(&event, &[speakers.clone(), headset.clone()], &headset.name),
+        None
+    );
+
+    let event = SinkRemoved { index: 2 };
+    let sinks = [speakers.clone(), headset.clone(), hdmi.clone()];
+    assert_eq!(
+        priority.sink_removed(&event, &sinks, &headset.name),
+        Some(speakers.name.clone())
+    );
+    assert_eq!(priority.sink_removed(&event, &sinks, &hdmi.name), None);
+    let event = SinkRemoved { index: 1 };
+    assert_eq!(
+        priority.sink_removed(&event, &[speakers.clone(), hdmi.clone()], &speakers.name),
+        Some(hdmi.name.clone())
+    );
+
+    let disabled = DevicePriority {
+        auto_switch: false,
+        ..priority
+    };
+    let event = SinkAdded { sink: headset };
+    assert_eq!(disabled.sink_added(&event, &[], &speakers.name), None);
+}
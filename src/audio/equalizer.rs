@@ -0,0 +1,517 @@
+use std::fmt::Display;
+
+use dbus::{
+    arg::{self, Append, Arg, ArgType, Get},
+    Signature,
+};
+use serde_json::{json, Map, Value};
+
+use crate::network::connection::Enum;
+
+#[derive(Debug)]
+pub struct EqualizerError(pub &'static str);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FilterType {
+    #[default]
+    Peaking,
+    LowShelf,
+    HighShelf,
+    LowPass,
+    HighPass,
+    Notch,
+}
+
+impl Enum for FilterType {
+    fn from_i32(num: i32) -> Self {
+        match num {
+            1 => FilterType::LowShelf,
+            2 => FilterType::HighShelf,
+            3 => FilterType::LowPass,
+            4 => FilterType::HighPass,
+            5 => FilterType::Notch,
+            _ => FilterType::Peaking,
+        }
+    }
+
+    fn to_i32(&self) -> i32 {
+        match self {
+            FilterType::Peaking => 0,
+            FilterType::LowShelf => 1,
+            FilterType::HighShelf => 2,
+            FilterType::LowPass => 3,
+            FilterType::HighPass => 4,
+            FilterType::Notch => 5,
+        }
+    }
+}
+
+impl Display for FilterType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FilterType::Peaking => f.write_str("Peaking"),
+            FilterType::LowShelf => f.write_str("Low Shelf"),
+            FilterType::HighShelf => f.write_str("High Shelf"),
+            FilterType::LowPass => f.write_str("Low Pass"),
+            FilterType::HighPass => f.write_str("High Pass"),
+            FilterType::Notch => f.write_str("Notch"),
+        }
+    }
+}
+
+impl FilterType {
+    fn from_autoeq(name: &str) -> Option<Self> {
+        match name {
+            "PK" | "PEQ" => Some(FilterType::Peaking),
+            "LSC" | "LS" | "LSQ" => Some(FilterType::LowShelf),
+            "HSC" | "HS" | "HSQ" => Some(FilterType::HighShelf),
+            "LP" | "LPQ" => Some(FilterType::LowPass),
+            "HP" | "HPQ" => Some(FilterType::HighPass),
+            "NO" => Some(FilterType::Notch),
+            _ => None,
+        }
+    }
+
+    fn autoeq_name(&self) -> &'static str {
+        match self {
+            FilterType::Peaking => "PK",
+            FilterType::LowShelf => "LSC",
+            FilterType::HighShelf => "HSC",
+            FilterType::LowPass => "LPQ",
+            FilterType::HighPass => "HPQ",
+            FilterType::Notch => "NO",
+        }
+    }
+
+    fn from_easyeffects(name: &str) -> Option<Self> {
+        match name {
+            "Bell" => Some(FilterType::Peaking),
+            "Lo-shelf" => Some(FilterType::LowShelf),
+            "Hi-shelf" => Some(FilterType::HighShelf),
+            "Lo-pass" => Some(FilterType::LowPass),
+            "Hi-pass" => Some(FilterType::HighPass),
+            "Notch" => Some(FilterType::Notch),
+            _ => None,
+        }
+    }
+
+    fn easyeffects_name(&self) -> &'static str {
+        match self {
+            FilterType::Peaking => "Bell",
+            FilterType::LowShelf => "Lo-shelf",
+            FilterType::HighShelf => "Hi-shelf",
+            FilterType::LowPass => "Lo-pass",
+            FilterType::HighPass => "Hi-pass",
+            FilterType::Notch => "Notch",
+        }
+    }
+}
+
+impl Append for FilterType {
+    fn append_by_ref(&self, iter: &mut arg::IterAppend) {
+        iter.append(self.to_i32());
+    }
+}
+
+impl<'a> Get<'a> for FilterType {
+    fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
+        Some(FilterType::from_i32(i32::get(i)?))
+    }
+}
+
+impl Arg for FilterType {
+    const ARG_TYPE: arg::ArgType = ArgType::Int32;
+    fn signature() -> Signature<'static> {
+        unsafe { Signature::from_slice_unchecked("i\0") }
+    }
+}
+
+/// Single filter of a parametric equalizer, frequency in Hz and gain in dB.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EqBand {
+    pub filter_type: FilterType,
+    pub frequency: f64,
+    pub gain: f64,
+    pub q: f64,
+}
+
+impl Default for EqBand {
+    fn default() -> Self {
+        Self {
+            filter_type: FilterType::Peaking,
+            frequency: 1000.0,
+            gain: 0.0,
+            q: std::f64::consts::FRAC_1_SQRT_2,
+        }
+    }
+}
+
+impl Append for EqBand {
+    fn append_by_ref(&self, iter: &mut arg::IterAppend) {
+        iter.append_struct(|i| {
+            i.append(self.filter_type);
+            i.append(self.frequency);
+            i.append(self.gain);
+            i.append(self.q);
+        });
+    }
+}
+
+impl<'a> Get<'a> for EqBand {
+    fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
+        let (filter_type, frequency, gain, q) = <(FilterType, f64, f64, f64)>::get(i)?;
+        Some(Self {
+            filter_type,
+            frequency,
+            gain,
+            q,
+        })
+    }
+}
+
+impl Arg for EqBand {
+    const ARG_TYPE: arg::ArgType = ArgType::Struct;
+    fn signature() -> Signature<'static> {
+        unsafe { Signature::from_slice_unchecked("(iddd)\0") }
+    }
+}
+
+/// The preamp in dB is applied before the bands to avoid clipping.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EqPreset {
+    pub name: String,
+    /// Name of the sink the preset is used for, empty if it is not bound.
+    pub sink: String,
+    pub preamp: f64,
+    pub bands: Vec<EqBand>,
+}
+
+impl Append for EqPreset {
+    fn append_by_ref(&self, iter: &mut arg::IterAppend) {
+        iter.append_struct(|i| {
+            i.append(&self.name);
+            i.append(&self.sink);
+            i.append(self.preamp);
+            i.append(&self.bands);
+        });
+    }
+}
+
+impl<'a> Get<'a> for EqPreset {
+    fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
+        let (name, sink, preamp, bands) = <(String, String, f64, Vec<EqBand>)>::get(i)?;
+        Some(Self {
+            name,
+            sink,
+            preamp,
+            bands,
+        })
+    }
+}
+
+impl Arg for EqPreset {
+    const ARG_TYPE: arg::ArgType = ArgType::Struct;
+    fn signature() -> Signature<'static> {
+        unsafe { Signature::from_slice_unchecked("(ssda(iddd))\0") }
+    }
+}
+
+impl EqPreset {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: String::from(name),
+            ..Default::default()
+        }
+    }
+
+    /// Parses an AutoEQ `ParametricEQ.txt`, disabled filters are skipped.
+    pub fn from_autoeq(name: &str, text: &str) -> Result<Self, EqualizerError> {
+        let mut preset = EqPreset::new(name);
+        for line in text.lines().map(str::trim) {
+            if let Some(preamp) = line.strip_prefix("Preamp:") {
+                preset.preamp = parse_unit(preamp, "dB")?;
+                continue;
+            }
+            let Some((_, filter)) = line.split_once(':') else {
+                continue;
+            };
+            if !line.starts_with("Filter") {
+                continue;
+            }
+            let tokens: Vec<&str> = filter.split_whitespace().collect();
+            if tokens.first() != Some(&"ON") {
+                continue;
+            }
+            let filter_type = tokens
+                .get(1)
+                .and_then(|name| FilterType::from_autoeq(name))
+                .ok_or(EqualizerError("Unknown AutoEQ filter type"))?;
+            let value = |key: &str| -> Result<Option<f64>, EqualizerError> {
+                let Some(position) = tokens.iter().position(|token| *token == key) else {
+                    return Ok(None);
+                };
+                tokens
+                    .get(position + 1)
+                    .map(|token| parse_number(token))
+                    .transpose()
+            };
+            let frequency = value("Fc")?.ok_or(EqualizerError("AutoEQ filter without Fc"))?;
+            let band = EqBand {
+                filter_type,
+                frequency,
+                gain: value("Gain")?.unwrap_or_default(),
+                q: value("Q")?.unwrap_or(EqBand::default().q),
+            };
+            preset.bands.push(band);
+        }
+        Ok(preset)
+    }
+
+    pub fn to_autoeq(&self) -> String {
+        let mut text = format!("Preamp: {:.1} dB\n", self.preamp);
+        for (index, band) in self.bands.iter().enumerate() {
+            text.push_str(&format!(
+                "Filter {}: ON {} Fc {} Hz Gain {:.1} dB Q {:.2}\n",
+                index + 1,
+                band.filter_type.autoeq_name(),
+                band.frequency,
+                band.gain,
+                band.q
+            ));
+        }
+        text
+    }
+
+    /// Parses the equalizer of an EasyEffects output or input preset.
+    /// Only the left channel is used when the channels are split.
+    pub fn from_easyeffects(name: &str, text: &str) -> Result<Self, EqualizerError> {
+        let json: Value = serde_json::from_str(text).map_err(|_| EqualizerError("Invalid JSON"))?;
+        let chain = json
+            .get("output")
+            .or_else(|| json.get("input"))
+            .and_then(Value::as_object)
+            .ok_or(EqualizerError("EasyEffects preset without output or input"))?;
+        // the first equalizer of the chain, older presets have no plugin order
+        let equalizer = match chain.get("plugins_order").and_then(Value::as_array) {
+            Some(order) => order
+                .iter()
+                .filter_map(Value::as_str)
+                .filter(|plugin| plugin.starts_with("equalizer"))
+                .find_map(|plugin| chain.get(plugin)),
+            None => chain
+                .iter()
+                .find(|(key, _)| key.starts_with("equalizer"))
+                .map(|(_, value)| value),
+        }
+        .ok_or(EqualizerError("EasyEffects preset without equalizer"))?;
+        let mut preset = EqPreset::new(name);
+        preset.preamp = equalizer
+            .get("input-gain")
+            .and_then(Value::as_f64)
+            .unwrap_or_default();
+        let Some(left) = equalizer.get("left").and_then(Value::as_object) else {
+            return Err(EqualizerError("EasyEffects equalizer without bands"));
+        };
+        let count = equalizer
+            .get("num-bands")
+            .and_then(Value::as_u64)
+            .map_or(left.len(), |count| left.len().min(count as usize));
+        for index in 0..count {
+            let Some(band) = left.get(&format!("band{}", index)) else {
+                continue;
+            };
+            let mode = band.get("type").and_then(Value::as_str).unwrap_or("Bell");
+            let muted = band.get("mute").and_then(Value::as_bool).unwrap_or(false);
+            let Some(filter_type) = FilterType::from_easyeffects(mode) else {
+                continue;
+            };
+            if muted {
+                continue;
+            }
+            let number = |key: &str| band.get(key).and_then(Value::as_f64);
+            preset.bands.push(EqBand {
+                filter_type,
+                frequency: number("frequency").unwrap_or(EqBand::default().frequency),
+                gain: number("gain").unwrap_or_default(),
+                q: number("q").unwrap_or(EqBand::default().q),
+            });
+        }
+        Ok(preset)
+    }
+
+    /// Writes an EasyEffects output preset containing only the equalizer.
+    /// Fails if a value is not finite, as JSON has no representation for it.
+    pub fn to_easyeffects(&self) -> Result<String, EqualizerError> {
+        let mut left = Map::new();
+        for (index, band) in self.bands.iter().enumerate() {
+            left.insert(
+                format!("band{}", index),
+                json!({
+                    "frequency": json_number(band.frequency)?,
+                    "gain": json_number(band.gain)?,
+                    "mode": "RLC (BT)",
+                    "mute": false,
+                    "q": json_number(band.q)?,
+                    "slope": "x1",
+                    "solo": false,
+                    "type": band.filter_type.easyeffects_name(),
+                }),
+            );
+        }
+        let preset = json!({
+            "output": {
+                "blocklist": [],
+                "plugins_order": ["equalizer#0"],
+                "equalizer#0": {
+                    "balance": 0.0,
+                    "bypass": false,
+                    "input-gain": json_number(self.preamp)?,
+                    "output-gain": 0.0,
+                    "mode": "IIR",
+                    "num-bands": self.bands.len(),
+                    "split-channels": false,
+                    "left": left.clone(),
+                    "right": left,
+                },
+            },
+        });
+        serde_json::to_string_pretty(&preset)
+            .map(|text| text + "\n")
+            .map_err(|_| EqualizerError("Could not write JSON"))
+    }
+}
+
+/// Preset bound to `sink`, falls back to a preset that is not bound to any sink.
+pub fn preset_for_sink<'a>(presets: &'a [EqPreset], sink: &str) -> Option<&'a EqPreset> {
+    presets
+        .iter()
+        .find(|preset| preset.sink == sink)
+        .or_else(|| presets.iter().find(|preset| preset.sink.is_empty()))
+}
+
+fn parse_unit(value: &str, unit: &str) -> Result<f64, EqualizerError> {
+    parse_number(value.trim().trim_end_matches(unit))
+}
+
+/// Parses a finite number, nan and infinity are rejected.
+fn parse_number(value: &str) -> Result<f64, EqualizerError> {
+    value
+        .trim()
+        .parse()
+        .ok()
+        .filter(|number: &f64| number.is_finite())
+        .ok_or(EqualizerError("Invalid number"))
+}
+
+fn json_number(value: f64) -> Result<Value, EqualizerError> {
+    serde_json::Number::from_f64(value)
+        .map(Value::Number)
+        .ok_or(EqualizerError("JSON numbers must be finite"))
+}
+
+#[test]
+fn autoeq_test() {
+    let text = "Preamp: -6.2 dB\n\
+                Filter 1: ON LSC Fc 105 Hz Gain 5.5 dB Q 0.70\n\
+                Filter 2: ON PK Fc 2263 Hz Gain -2.1 dB Q 1.41\n\
+                Filter 3: OFF PK Fc 4000 Hz Gain 1.0 dB Q 1.00\n\
+                Filter 4: ON HSC Fc 10000 Hz Gain 2.0 dB Q 0.70\n";
+    let preset = EqPreset::from_autoeq("HD 650", text).unwrap();
+    assert_eq!(preset.preamp, -6.2);
+    assert_eq!(preset.bands.len(), 3);
+    assert_eq!(preset.bands[0].filter_type, FilterType::LowShelf);
+    assert_eq!(preset.bands[1].frequency, 2263.0);
+    assert_eq!(preset.bands[1].gain, -2.1);
+    assert_eq!(preset.bands[2].q, 0.7);
+
+    let exported = preset.to_autoeq();
+    assert!(exported.starts_with("Preamp: -6.2 dB\nFilter 1: ON LSC Fc 105 Hz Gain 5.5 dB Q 0.70"));
+    assert_eq!(EqPreset::from_autoeq("HD 650", &exported).unwrap(), preset);
+    assert!(EqPreset::from_autoeq("broken", "Filter 1: ON XYZ Fc 10 Hz").is_err());
+    assert!(EqPreset::from_autoeq("broken", "Filter 1: ON PK Fc 100 Hz Gain nan dB").is_err());
+    assert!(EqPreset::from_autoeq("broken", "Preamp: inf dB").is_err());
+}
+
+#[test]
+fn easyeffects_test() {
+    let mut preset = EqPreset::new("Bass");
+    preset.preamp = -3.0;
+    preset.bands = vec![
+        EqBand {
+            filter_type: FilterType::LowShelf,
+            frequency: 100.0,
+            gain: 4.5,
+            q: 0.7,
+        },
+        EqBand {
+            filter_type: FilterType::Notch,
+            frequency: 6000.0,
+            gain: 0.0,
+            q: 4.0,
+        },
+    ];
+    let json = preset.to_easyeffects().unwrap();
+    let parsed = EqPreset::from_easyeffects("Bass", &json).unwrap();
+    assert_eq!(parsed, preset);
+    preset.bands[1].gain = f64::NAN;
+    assert!(preset.to_easyeffects().is_err());
+
+    let legacy = r#"{"output": {"equalizer": {"input-gain": 1.5, "num-bands": 2,
+        "left": {"band0": {"frequency": 32.0, "gain": -1.0, "q": 1.5, "type": "Bell"},
+                 "band1": {"frequency": 64.0, "gain": 2.0, "q": 1.5, "type": "Off"}}}}}"#;
+    let parsed = EqPreset::from_easyeffects("Legacy", legacy).unwrap();
+    assert_eq!(parsed.preamp, 1.5);
+    assert_eq!(parsed.bands.len(), 1);
+    assert!(EqPreset::from_easyeffects("broken", "{\"output\": [}").is_err());
+
+    let deep = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+    assert!(EqPreset::from_easyeffects("broken", &deep).is_err());
+
+    let huge = r#"{"output": {"equalizer#0": {"num-bands": 1000000000000000000,
+        "left": {"band0": {"frequency": 32.0, "type": "Bell"}}}}}"#;
+    assert_eq!(
+        EqPreset::from_easyeffects("Huge", huge)
+            .unwrap()
+            .bands
+            .len(),
+        1
+    );
+    let huge = huge.replace("1000000000000000000", "1e18");
+    assert_eq!(
+        EqPreset::from_easyeffects("Huge", &huge)
+            .unwrap()
+            .bands
+            .len(),
+        1
+    );
+
+    let chain = r#"{"output": {"plugins_order": ["equalizer#1", "equalizer#0"],
+        "equalizer#0": {"left": {"band0": {"frequency": 100.0}}},
+        "equalizer#1": {"left": {"band0": {"frequency": 200.0}}}}}"#;
+    let parsed = EqPreset::from_easyeffects("Chain", chain).unwrap();
+    assert_eq!(parsed.bands[0].frequency, 200.0);
+}
+
+#[test]
+fn eq_preset_dbus_test() {
+    let mut preset = EqPreset::new("Headphones");
+    preset.sink = String::from("alsa_output.usb-headset");
+    preset.bands.push(EqBand::default());
+    let message = dbus::Message::new_signal("/test", "org.test", "Test")
+        .unwrap()
+        .append1(&preset);
+    assert_eq!(message.iter_init().signature().to_string(), "(ssda(iddd))");
+    let received: EqPreset = message.read1().unwrap();
+    assert_eq!(received, preset);
+    let presets = [EqPreset::new("Default"), received];
+    assert_eq!(
+        preset_for_sink(&presets, "alsa_output.usb-headset")
+            .unwrap()
+            .name,
+        "Headphones"
+    );
+    assert_eq!(
+        preset_for_sink(&presets, "speakers").unwrap().name,
+        "Default"
+    );
+}
@@ -0,0 +1,114 @@
+use std::time::{Duration, Instant};
+
+use crate::signals::LevelMeter;
+
+/// Default time between two [`LevelMeter`] signals, roughly 30 updates per second.
+pub const DEFAULT_LEVEL_INTERVAL: Duration = Duration::from_millis(33);
+
+/// Turns the samples of a peak detect monitor stream into rate limited [`LevelMeter`] signals.
+/// Samples are expected as interleaved float32, which is what pulse delivers for streams
+/// created with the PEAK_DETECT flag. The highest peak per channel is kept until the next
+/// signal is emitted, so short peaks between two signals are not lost.
+#[derive(Debug, Clone)]
+pub struct LevelMeterProducer {
+    index: u32,
+    is_source: bool,
+    interval: Duration,
+    peaks: Vec<f64>,
+    last_emit: Option<Instant>,
+}
+
+impl LevelMeterProducer {
+    pub fn new(index: u32, is_source: bool, channels: u16) -> Self {
+        Self::with_interval(index, is_source, channels, DEFAULT_LEVEL_INTERVAL)
+    }
+
+    pub fn with_interval(index: u32, is_source: bool, channels: u16, interval: Duration) -> Self {
+        Self {
+            index,
+            is_source,
+            interval,
+            peaks: vec![0.0; channels.max(1) as usize],
+            last_emit: None,
+        }
+    }
+
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn is_source(&self) -> bool {
+        self.is_source
+    }
+
+    pub fn channels(&self) -> usize {
+        self.peaks.len()
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Feeds interleaved samples, returns a signal once the interval has passed.
+    pub fn push_samples(&mut self, samples: &[f32]) -> Option<LevelMeter> {
+        self.push_samples_at(samples, Instant::now())
+    }
+
+    /// Same as [`LevelMeterProducer::push_samples`] with raw native endian bytes as delivered
+    /// by a pulse record stream. Incomplete trailing samples are ignored.
+    pub fn push_bytes(&mut self, bytes: &[u8]) -> Option<LevelMeter> {
+        let samples: Vec<f32> = bytes
+            .chunks_exact(4)
+            .map(|sample| f32::from_ne_bytes([sample[0], sample[1], sample[2], sample[3]]))
+            .collect();
+        self.push_samples(&samples)
+    }
+
+    pub fn push_samples_at(&mut self, samples: &[f32], now: Instant) -> Option<LevelMeter> {
+        let channels = self.peaks.len();
+        for (position, sample) in samples.iter().enumerate() {
+            let peak = &mut self.peaks[position % channels];
+            let sample = (sample.abs() as f64).clamp(0.0, 1.0);
+            if sample > *peak {
+                *peak = sample;
+            }
+        }
+        if let Some(last_emit) = self.last_emit {
+            if now.duration_since(last_emit) < self.interval {
+                return None;
+            }
+        }
+        self.last_emit = Some(now);
+        let peaks = std::mem::replace(&mut self.peaks, vec![0.0; channels]);
+        Some(LevelMeter {
+            index: self.index,
+            is_source: self.is_source,
+            peaks,
+        })
+    }
+}
+
+#[test]
+fn level_meter_producer_test() {
+    let start = Instant::now();
+    let mut producer = LevelMeterProducer::with_interval(3, true, 2, Duration::from_millis(100));
+    let meter = producer
+        .push_samples_at(&[0.5, -0.25], start)
+        .expect("first samples should emit");
+    assert_eq!(meter.index, 3);
+    assert!(meter.is_source);
+    assert_eq!(meter.peaks, vec![0.5, 0.25]);
+
+    assert!(producer
+        .push_samples_at(&[0.75, 0.0, 0.125, 2.0], start + Duration::from_millis(50))
+        .is_none());
+    let meter = producer
+        .push_samples_at(&[0.0, 0.0], start + Duration::from_millis(100))
+        .unwrap();
+    assert_eq!(meter.peaks, vec![0.75, 1.0]);
+
+    let meter = producer
+        .push_samples_at(&[], start + Duration::from_millis(200))
+        .unwrap();
+    assert_eq!(meter.peaks, vec![0.0, 0.0]);
+}
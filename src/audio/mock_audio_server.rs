@@ -0,0 +1,648 @@
+use crate::signals::{
+    DefaultSinkChanged, DefaultSourceChanged, InputStreamAdded, InputStreamChanged,
+    InputStreamRemoved, OutputStreamAdded, OutputStreamChanged, OutputStreamRemoved, SinkAdded,
+    SinkChanged, SinkRemoved, SourceAdded, SourceChanged, SourceRemoved,
+};
+
+use super::audio_backend::{AudioBackend, AudioEvent};
+use super::audio_changes::ChangeCoalescer;
+use super::audio_structures::{
+    Card, ChannelVolumes, InputStream, LatencyOffset, OutputStream, ServerInfo, Sink, Source,
+    INVALID_INDEX,
+};
+
+/// In-memory audio server for tests and frontend development without PulseAudio or PipeWire.
+/// Every mutation queues the signal the real daemon would send, use
+/// [`MockAudioServer::take_events`] to consume them.
+/// Like pulse, sinks, sources, cards and both stream kinds use separate index ranges.
+#[derive(Debug, Default)]
+pub struct MockAudioServer {
+    server_info: ServerInfo,
+    sinks: Vec<Sink>,
+    sources: Vec<Source>,
+    cards: Vec<Card>,
+    input_streams: Vec<InputStream>,
+    output_streams: Vec<OutputStream>,
+    next_sink_index: u32,
+    next_source_index: u32,
+    next_card_index: u32,
+    next_input_stream_index: u32,
+    next_output_stream_index: u32,
+    events: ChangeCoalescer,
+}
+
+impl MockAudioServer {
+    pub fn new() -> Self {
+        Self {
+            server_info: ServerInfo {
+                server_name: String::from("ReSet Mock Audio Server"),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    pub fn server_info(&self) -> &ServerInfo {
+        &self.server_info
+    }
+
+    pub fn sinks(&self) -> &[Sink] {
+        &self.sinks
+    }
+
+    pub fn sources(&self) -> &[Source] {
+        &self.sources
+    }
+
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    pub fn input_streams(&self) -> &[InputStream] {
+        &self.input_streams
+    }
+
+    pub fn output_streams(&self) -> &[OutputStream] {
+        &self.output_streams
+    }
+
+    pub fn sink(&self, index: u32) -> Option<&Sink> {
+        self.sinks.iter().find(|sink| sink.index == index)
+    }
+
+    pub fn source(&self, index: u32) -> Option<&Source> {
+        self.sources.iter().find(|source| source.index == index)
+    }
+
+    pub fn card(&self, index: u32) -> Option<&Card> {
+        self.cards.iter().find(|card| card.index == index)
+    }
+
+    pub fn input_stream(&self, index: u32) -> Option<&InputStream> {
+        self.input_streams
+            .iter()
+            .find(|stream| stream.index == index)
+    }
+
+    pub fn output_stream(&self, index: u32) -> Option<&OutputStream> {
+        self.output_streams
+            .iter()
+            .find(|stream| stream.index == index)
+    }
+
+    /// Returns all queued signals in the order they were emitted.
+    /// Changed signals of the same object since the last call are merged into one, without
+    /// waiting for a coalesce window.
+    pub fn take_events(&mut self) -> Vec<AudioEvent> {
+        self.events.flush()
+    }
+
+    /// The first sink becomes the default sink.
+    pub fn add_sink(&mut self, mut sink: Sink) -> u32 {
+        sink.index = self.next_sink_index;
+        self.next_sink_index += 1;
+        let index = sink.index;
+        let name = sink.name.clone();
+        self.sinks.push(sink.clone());
+        self.events.push(AudioEvent::SinkAdded(SinkAdded { sink }));
+        if self.server_info.default_sink.is_empty() {
+            self.set_default_sink(&name);
+        }
+        index
+    }
+
+    /// Applies `change` to the sink and emits [`SinkChanged`] if anything changed.
+    /// The index of the sink can not be changed.
+    pub fn update_sink(&mut self, index: u32, change: impl FnOnce(&mut Sink)) -> bool {
+        let Some(sink) = self.sinks.iter_mut().find(|sink| sink.index == index) else {
+            return false;
+        };
+        let previous = sink.clone();
+        change(sink);
+        sink.index = index;
+        let changes = sink.changes(&previous);
+        if changes.is_empty() {
+            return true;
+        }
+        let sink = sink.clone();
+        self.events
+            .push(AudioEvent::SinkChanged(SinkChanged { sink, changes }));
+        true
+    }
+
+    pub fn set_sink_volume(&mut self, index: u32, volume: ChannelVolumes) -> bool {
+        self.update_sink(index, |sink| sink.volume = volume)
+    }
+
+    pub fn set_sink_mute(&mut self, index: u32, muted: bool) -> bool {
+        self.update_sink(index, |sink| sink.muted = muted)
+    }
+
+    pub fn set_default_sink(&mut self, name: &str) -> bool {
+        if !self.sinks.iter().any(|sink| sink.name == name) {
+            return false;
+        }
+        if self.server_info.default_sink != name {
+            self.server_info.default_sink = String::from(name);
+            self.events
+                .push(AudioEvent::DefaultSinkChanged(DefaultSinkChanged {
+                    name: String::from(name),
+                }));
+        }
+        true
+    }
+
+    /// Removes the sink, streams playing on it are moved to the (new) default sink.
+    /// Like pulse, the default sink is kept and the streams are removed when no sink
+    /// remains.
+    pub fn remove_sink(&mut self, index: u32) -> bool {
+        let Some(position) = self.sinks.iter().position(|sink| sink.index == index) else {
+            return false;
+        };
+        let sink = self.sinks.remove(position);
+        self.events
+            .push(AudioEvent::SinkRemoved(SinkRemoved { index }));
+        if self.server_info.default_sink == sink.name {
+            if let Some(fallback) = self.sinks.first().map(|sink| sink.name.clone()) {
+                self.set_default_sink(&fallback);
+            }
+        }
+        let fallback_index = self.default_sink_index();
+        let moved: Vec<u32> = self
+            .input_streams
+            .iter()
+            .filter(|stream| stream.sink_index == index)
+            .map(|stream| stream.index)
+            .collect();
+        for stream in moved {
+            if fallback_index == INVALID_INDEX {
+                self.remove_input_stream(stream);
+            } else {
+                self.update_input_stream(stream, |stream| stream.sink_index = fallback_index);
+            }
+        }
+        true
+    }
+
+    /// The first source becomes the default source.
+    pub fn add_source(&mut self, mut source: Source) -> u32 {
+        source.index = self.next_source_index;
+        self.next_source_index += 1;
+        let index = source.index;
+        let name = source.name.clone();
+        self.sources.push(source.clone());
+        self.events
+            .push(AudioEvent::SourceAdded(SourceAdded { source }));
+        if self.server_info.default_source.is_empty() {
+            self.set_default_source(&name);
+        }
+        index
+    }
+
+    /// Applies `change` to the source and emits [`SourceChanged`] if anything changed.
+    /// The index of the source can not be changed.
+    pub fn update_source(&mut self, index: u32, change: impl FnOnce(&mut Source)) -> bool {
+        let Some(source) = self.sources.iter_mut().find(|source| source.index == index) else {
+            return false;
+        };
+        let previous = source.clone();
+        change(source);
+        source.index = index;
+        let changes = source.changes(&previous);
+        if changes.is_empty() {
+            return true;
+        }
+        let source = source.clone();
+        self.events
+            .push(AudioEvent::SourceChanged(SourceChanged { source, changes }));
+        true
+    }
+
+    pub fn set_source_volume(&mut self, index: u32, volume: ChannelVolumes) -> bool {
+        self.update_source(index, |source| source.volume = volume)
+    }
+
+    pub fn set_source_mute(&mut self, index: u32, muted: bool) -> bool {
+        self.update_source(index, |source| source.muted = muted)
+    }
+
+    pub fn set_default_source(&mut self, name: &str) -> bool {
+        if !self.sources.iter().any(|source| source.name == name) {
+            return false;
+        }
+        if self.server_info.default_source != name {
+            self.server_info.default_source = String::from(name);
+            self.events
+                .push(AudioEvent::DefaultSourceChanged(DefaultSourceChanged {
+                    name: String::from(name),
+                }));
+        }
+        true
+    }
+
+    /// Removes the source, streams recording from it are moved to the (new) default source.
+    /// Like pulse, the default source is kept and the streams are removed when no source
+    /// remains.
+    pub fn remove_source(&mut self, index: u32) -> bool {
+        let Some(position) = self.sources.iter().position(|source| source.index == index) else {
+            return false;
+        };
+        let source = self.sources.remove(position);
+        self.events
+            .push(AudioEvent::SourceRemoved(SourceRemoved { index }));
+        if self.server_info.default_source == source.name {
+            if let Some(fallback) = self.sources.first().map(|source| source.name.clone()) {
+                self.set_default_source(&fallback);
+            }
+        }
+        let fallback_index = self.default_source_index();
+        let moved: Vec<u32> = self
+            .output_streams
+            .iter()
+            .filter(|stream| stream.source_index == index)
+            .map(|stream| stream.index)
+            .collect();
+        for stream in moved {
+            if fallback_index == INVALID_INDEX {
+                self.remove_output_stream(stream);
+            } else {
+                self.update_output_stream(stream, |stream| stream.source_index = fallback_index);
+            }
+        }
+        true
+    }
+
+    /// Cards have no signals of their own, profile changes only show up on the devices.
+    pub fn add_card(&mut self, mut card: Card) -> u32 {
+        card.index = self.next_card_index;
+        self.next_card_index += 1;
+        let index = card.index;
+        self.cards.push(card);
+        index
+    }
+
+    /// Fails if the card does not offer `profile`.
+    pub fn set_card_profile(&mut self, index: u32, profile: &str) -> bool {
+        let Some(card) = self.cards.iter_mut().find(|card| card.index == index) else {
+            return false;
+        };
+        if !card.profiles.iter().any(|entry| entry.name == profile) {
+            return false;
+        }
+        card.active_profile = String::from(profile);
+        true
+    }
+
+    /// Fails if the card or port does not exist.
+    pub fn set_port_latency_offset(&mut self, offset: &LatencyOffset) -> bool {
+        let port = self
+            .cards
+            .iter_mut()
+            .filter(|card| card.system_name == offset.card_name)
+            .flat_map(|card| card.ports.iter_mut())
+            .find(|port| port.name == offset.port);
+        let Some(port) = port else {
+            return false;
+        };
+        port.latency_offset = offset.offset;
+        true
+    }
+
+    pub fn remove_card(&mut self, index: u32) -> bool {
+        let length = self.cards.len();
+        self.cards.retain(|card| card.index != index);
+        length != self.cards.len()
+    }
+
+    /// Streams without a valid sink are placed on the default sink.
+    pub fn add_input_stream(&mut self, mut stream: InputStream) -> u32 {
+        stream.index = self.next_input_stream_index;
+        self.next_input_stream_index += 1;
+        if self.sink(stream.sink_index).is_none() {
+            stream.sink_index = self.default_sink_index();
+        }
+        let index = stream.index;
+        self.input_streams.push(stream.clone());
+        self.events
+            .push(AudioEvent::InputStreamAdded(InputStreamAdded { stream }));
+        index
+    }
+
+    /// Applies `change` to the stream and emits [`InputStreamChanged`] if anything changed.
+    /// The index of the stream can not be changed.
+    pub fn update_input_stream(
+        &mut self,
+        index: u32,
+        change: impl FnOnce(&mut InputStream),
+    ) -> bool {
+        let Some(stream) = self
+            .input_streams
+            .iter_mut()
+            .find(|stream| stream.index == index)
+        else {
+            return false;
+        };
+        let previous = stream.clone();
+        change(stream);
+        stream.index = index;
+        let changes = stream.changes(&previous);
+        if changes.is_empty() {
+            return true;
+        }
+        let stream = stream.clone();
+        self.events
+            .push(AudioEvent::InputStreamChanged(InputStreamChanged {
+                stream,
+                changes,
+            }));
+        true
+    }
+
+    pub fn set_input_stream_volume(&mut self, index: u32, volume: ChannelVolumes) -> bool {
+        self.update_input_stream(index, |stream| stream.volume = volume)
+    }
+
+    pub fn set_input_stream_mute(&mut self, index: u32, muted: bool) -> bool {
+        self.update_input_stream(index, |stream| stream.muted = muted)
+    }
+
+    pub fn move_input_stream(&mut self, index: u32, sink_index: u32) -> bool {
+        if self.sink(sink_index).is_none() {
+            return false;
+        }
+        self.update_input_stream(index, |stream| stream.sink_index = sink_index)
+    }
+
+    pub fn remove_input_stream(&mut self, index: u32) -> bool {
+        let length = self.input_streams.len();
+        self.input_streams.retain(|stream| stream.index != index);
+        if length == self.input_streams.len() {
+            return false;
+        }
+        self.events
+            .push(AudioEvent::InputStreamRemoved(InputStreamRemoved { index }));
+        true
+    }
+
+    /// Streams without a valid source are placed on the default source.
+    pub fn add_output_stream(&mut self, mut stream: OutputStream) -> u32 {
+        stream.index = self.next_output_stream_index;
+        self.next_output_stream_index += 1;
+        if self.source(stream.source_index).is_none() {
+            stream.source_index = self.default_source_index();
+        }
+        let index = stream.index;
+        self.output_streams.push(stream.clone());
+        self.events
+            .push(AudioEvent::OutputStreamAdded(OutputStreamAdded { stream }));
+        index
+    }
+
+    /// Applies `change` to the stream and emits [`OutputStreamChanged`] if anything changed.
+    /// The index of the stream can not be changed.
+    pub fn update_output_stream(
+        &mut self,
+        index: u32,
+        change: impl FnOnce(&mut OutputStream),
+    ) -> bool {
+        let Some(stream) = self
+            .output_streams
+            .iter_mut()
+            .find(|stream| stream.index == index)
+        else {
+            return false;
+        };
+        let previous = stream.clone();
+        change(stream);
+        stream.index = index;
+        let changes = stream.changes(&previous);
+        if changes.is_empty() {
+            return true;
+        }
+        let stream = stream.clone();
+        self.events
+            .push(AudioEvent::OutputStreamChanged(OutputStreamChanged {
+                stream,
+                changes,
+            }));
+        true
+    }
+
+    pub fn set_output_stream_volume(&mut self, index: u32, volume: ChannelVolumes) -> bool {
+        self.update_output_stream(index, |stream| stream.volume = volume)
+    }
+
+    pub fn set_output_stream_mute(&mut self, index: u32, muted: bool) -> bool {
+        self.update_output_stream(index, |stream| stream.muted = muted)
+    }
+
+    pub fn move_output_stream(&mut self, index: u32, source_index: u32) -> bool {
+        if self.source(source_index).is_none() {
+            return false;
+        }
+        self.update_output_stream(index, |stream| stream.source_index = source_index)
+    }
+
+    pub fn remove_output_stream(&mut self, index: u32) -> bool {
+        let length = self.output_streams.len();
+        self.output_streams.retain(|stream| stream.index != index);
+        if length == self.output_streams.len() {
+            return false;
+        }
+        self.events
+            .push(AudioEvent::OutputStreamRemoved(OutputStreamRemoved {
+                index,
+            }));
+        true
+    }
+
+    fn default_sink_index(&self) -> u32 {
+        self.sinks
+            .iter()
+            .find(|sink| sink.name == self.server_info.default_sink)
+            .map(|sink| sink.index)
+            .unwrap_or(INVALID_INDEX)
+    }
+
+    fn default_source_index(&self) -> u32 {
+        self.sources
+            .iter()
+            .find(|source| source.name == self.server_info.default_source)
+            .map(|source| source.index)
+            .unwrap_or(INVALID_INDEX)
+    }
+}
+
+/// Events are queued from the start, [`AudioBackend::subscribe`] always succeeds.
+impl AudioBackend for MockAudioServer {
+    fn server_info(&mut self) -> Option<ServerInfo> {
+        Some(self.server_info.clone())
+    }
+
+    fn sinks(&mut self) -> Vec<Sink> {
+        self.sinks.clone()
+    }
+
+    fn sources(&mut self) -> Vec<Source> {
+        self.sources.clone()
+    }
+
+    fn cards(&mut self) -> Vec<Card> {
+        self.cards.clone()
+    }
+
+    fn input_streams(&mut self) -> Vec<InputStream> {
+        self.input_streams.clone()
+    }
+
+    fn output_streams(&mut self) -> Vec<OutputStream> {
+        self.output_streams.clone()
+    }
+
+    fn subscribe(&mut self) -> bool {
+        true
+    }
+
+    fn take_events(&mut self) -> Vec<AudioEvent> {
+        MockAudioServer::take_events(self)
+    }
+
+    fn set_sink_volume(&mut self, index: u32, volume: &ChannelVolumes) -> bool {
+        MockAudioServer::set_sink_volume(self, index, volume.clone())
+    }
+
+    fn set_sink_mute(&mut self, index: u32, muted: bool) -> bool {
+        MockAudioServer::set_sink_mute(self, index, muted)
+    }
+
+    fn set_default_sink(&mut self, name: &str) -> bool {
+        MockAudioServer::set_default_sink(self, name)
+    }
+
+    fn set_source_volume(&mut self, index: u32, volume: &ChannelVolumes) -> bool {
+        MockAudioServer::set_source_volume(self, index, volume.clone())
+    }
+
+    fn set_source_mute(&mut self, index: u32, muted: bool) -> bool {
+        MockAudioServer::set_source_mute(self, index, muted)
+    }
+
+    fn set_default_source(&mut self, name: &str) -> bool {
+        MockAudioServer::set_default_source(self, name)
+    }
+
+    fn set_card_profile(&mut self, index: u32, profile: &str) -> bool {
+        MockAudioServer::set_card_profile(self, index, profile)
+    }
+
+    fn set_port_latency_offset(&mut self, offset: &LatencyOffset) -> bool {
+        MockAudioServer::set_port_latency_offset(self, offset)
+    }
+
+    fn set_input_stream_volume(&mut self, index: u32, volume: &ChannelVolumes) -> bool {
+        MockAudioServer::set_input_stream_volume(self, index, volume.clone())
+    }
+
+    fn set_input_stream_mute(&mut self, index: u32, muted: bool) -> bool {
+        MockAudioServer::set_input_stream_mute(self, index, muted)
+    }
+
+    fn move_input_stream(&mut self, index: u32, sink_index: u32) -> bool {
+        MockAudioServer::move_input_stream(self, index, sink_index)
+    }
+
+    fn set_output_stream_volume(&mut self, index: u32, volume: &ChannelVolumes) -> bool {
+        MockAudioServer::set_output_stream_volume(self, index, volume.clone())
+    }
+
+    fn set_output_stream_mute(&mut self, index: u32, muted: bool) -> bool {
+        MockAudioServer::set_output_stream_mute(self, index, muted)
+    }
+
+    fn move_output_stream(&mut self, index: u32, source_index: u32) -> bool {
+        MockAudioServer::move_output_stream(self, index, source_index)
+    }
+}
+
+#[test]
+fn mock_audio_server_test() {
+    use super::audio_changes::ChangeMask;
+
+    let mut server = MockAudioServer::new();
+    let speakers = server.add_sink(Sink {
+        name: String::from("speakers"),
+        ..Default::default()
+    });
+    let headset = server.add_sink(Sink {
+        name: String::from("headset"),
+        ..Default::default()
+    });
+    let stream = server.add_input_stream(InputStream {
+        application_name: String::from("Firefox"),
+        sink_index: headset,
+        ..Default::default()
+    });
+    assert_eq!(server.server_info().default_sink, "speakers");
+    assert!(server.set_sink_volume(speakers, ChannelVolumes::from_raw(&[32768, 32768])));
+    assert!(!server.set_sink_mute(42, true));
+    assert!(server.remove_sink(headset));
+    assert_eq!(server.input_stream(stream).unwrap().sink_index, speakers);
+
+    let events = server.take_events();
+    assert!(server.take_events().is_empty());
+    assert_eq!(events.len(), 7);
+    assert!(matches!(&events[0], AudioEvent::SinkAdded(event) if event.sink.index == speakers));
+    assert!(
+        matches!(&events[1], AudioEvent::DefaultSinkChanged(event) if event.name == "speakers")
+    );
+    assert!(matches!(&events[2], AudioEvent::SinkAdded(event) if event.sink.index == headset));
+    assert!(matches!(&events[3], AudioEvent::InputStreamAdded(_)));
+    assert!(
+        matches!(&events[4], AudioEvent::SinkChanged(event) if event.sink.volume.to_raw() == vec![32768, 32768] && event.changes == ChangeMask::VOLUME)
+    );
+    assert!(matches!(&events[5], AudioEvent::SinkRemoved(event) if event.index == headset));
+    assert!(
+        matches!(&events[6], AudioEvent::InputStreamChanged(event) if event.stream.sink_index == speakers && event.changes == ChangeMask::DEVICE)
+    );
+
+    assert!(server.remove_sink(speakers));
+    assert_eq!(server.server_info().default_sink, "speakers");
+    assert!(server.input_streams().is_empty());
+    let events = server.take_events();
+    assert_eq!(events.len(), 2);
+    assert!(matches!(&events[0], AudioEvent::SinkRemoved(event) if event.index == speakers));
+    assert!(matches!(&events[1], AudioEvent::InputStreamRemoved(event) if event.index == stream));
+}
+
+#[test]
+fn mock_audio_backend_test() {
+    use super::audio_changes::ChangeMask;
+
+    let mut server = MockAudioServer::new();
+    server.add_source(Source {
+        name: String::from("microphone"),
+        ..Default::default()
+    });
+    let backend: &mut dyn AudioBackend = &mut server;
+    assert!(backend.subscribe());
+    assert_eq!(backend.sources().len(), 1);
+    assert!(backend.set_source_mute(0, true));
+    assert!(!backend.set_default_source("missing"));
+    assert_eq!(backend.server_info().unwrap().default_source, "microphone");
+    let events = backend.take_events();
+    assert_eq!(events.len(), 3);
+    assert!(matches!(&events[2], AudioEvent::SourceChanged(event) if event.source.muted));
+
+    server.add_sink(Sink::default());
+    server.take_events();
+    let backend: &mut dyn AudioBackend = &mut server;
+    for volume in [1000, 2000, 3000] {
+        assert!(backend.set_sink_volume(0, &ChannelVolumes::from_raw(&[volume])));
+    }
+    assert!(backend.set_sink_mute(0, true));
+    let events = backend.take_events();
+    assert_eq!(events.len(), 1);
+    assert!(
+        matches!(&events[0], AudioEvent::SinkChanged(event) if event.sink.volume.to_raw() == vec![3000] && event.sink.muted && event.changes == ChangeMask::VOLUME | ChangeMask::MUTE)
+    );
+}
@@ -1 +1,12 @@
+pub mod audio_backend;
+pub mod audio_changes;
+pub mod audio_graph;
+pub mod audio_modules;
+pub mod audio_snapshot;
 pub mod audio_structures;
+pub mod device_priority;
+pub mod equalizer;
+pub mod level_meter;
+pub mod mock_audio_server;
+pub mod pulse_backend;
+pub mod stream_rules;
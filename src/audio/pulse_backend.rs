@@ -0,0 +1,573 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+};
+
+use pulse::{
+    callbacks::ListResult,
+    context::{
+        introspect::Introspector,
+        subscribe::{Facility, InterestMaskSet, Operation as SubscribeOperation},
+        Context, FlagSet as ContextFlagSet, State,
+    },
+    mainloop::standard::{IterateResult, Mainloop},
+    operation::{Operation, State as OperationState},
+    proplist::{properties::APPLICATION_NAME, Proplist},
+};
+
+use crate::signals::{
+    DefaultSinkChanged, DefaultSourceChanged, InputStreamAdded, InputStreamChanged,
+    InputStreamRemoved, OutputStreamAdded, OutputStreamChanged, OutputStreamRemoved, SinkAdded,
+    SinkChanged, SinkRemoved, SourceAdded, SourceChanged, SourceRemoved,
+};
+
+use super::audio_backend::{AudioBackend, AudioEvent};
+use super::audio_changes::{ChangeCoalescer, ChangeMask};
+use super::audio_structures::{
+    Card, ChannelVolumes, InputStream, LatencyOffset, OutputStream, ServerInfo, Sink, Source,
+};
+
+type SuccessCallback = Option<Box<dyn FnMut(bool) + 'static>>;
+/// State of an info request started for a subscription event.
+type PendingRequests = Rc<RefCell<Vec<Box<dyn Fn() -> OperationState>>>>;
+
+/// Last known state of all objects, used to compute the [`ChangeMask`] of changed events.
+#[derive(Default)]
+struct KnownObjects {
+    sinks: HashMap<u32, Sink>,
+    sources: HashMap<u32, Source>,
+    input_streams: HashMap<u32, InputStream>,
+    output_streams: HashMap<u32, OutputStream>,
+}
+
+/// Stores `object` and returns the fields that changed, None if nothing did.
+/// Objects that were not known yet are treated as entirely changed.
+fn update_known<T: Clone>(
+    known: &mut HashMap<u32, T>,
+    index: u32,
+    object: &T,
+    changes: impl Fn(&T, &T) -> ChangeMask,
+) -> Option<ChangeMask> {
+    let changes = match known.insert(index, object.clone()) {
+        Some(previous) => changes(object, &previous),
+        None => ChangeMask::ALL,
+    };
+    (!changes.is_empty()).then_some(changes)
+}
+
+/// Works with PulseAudio and pipewire-pulse.
+/// Uses a standard mainloop which is only iterated inside the backend calls, subscription
+/// events are therefore collected when calling [`AudioBackend::take_events`].
+pub struct PulseBackend {
+    mainloop: Mainloop,
+    context: Context,
+    events: Rc<RefCell<ChangeCoalescer>>,
+    requests: PendingRequests,
+}
+
+impl PulseBackend {
+    /// Connects to the default server, returns None if no server is reachable.
+    pub fn connect() -> Option<Self> {
+        let mut mainloop = Mainloop::new()?;
+        let mut proplist = Proplist::new()?;
+        proplist.set_str(APPLICATION_NAME, "ReSet").ok()?;
+        let mut context = Context::new_with_proplist(&mainloop, "ReSet", &proplist)?;
+        context.connect(None, ContextFlagSet::NOFLAGS, None).ok()?;
+        loop {
+            if let IterateResult::Quit(_) | IterateResult::Err(_) = mainloop.iterate(true) {
+                return None;
+            }
+            match context.get_state() {
+                State::Ready => break,
+                State::Failed | State::Terminated => return None,
+                _ => (),
+            }
+        }
+        Some(Self {
+            mainloop,
+            context,
+            events: Rc::new(RefCell::new(ChangeCoalescer::default())),
+            requests: Rc::new(RefCell::new(Vec::new())),
+        })
+    }
+
+    /// Iterates the mainloop until the operation is done, returns false on cancellation.
+    fn wait_for<T: ?Sized>(&mut self, mut operation: Operation<T>) -> bool {
+        while operation.get_state() == OperationState::Running {
+            if let IterateResult::Quit(_) | IterateResult::Err(_) = self.mainloop.iterate(true) {
+                operation.cancel();
+                return false;
+            }
+        }
+        operation.get_state() == OperationState::Done
+    }
+
+    fn wait_for_success(
+        &mut self,
+        request: impl FnOnce(&mut Context, SuccessCallback) -> Operation<dyn FnMut(bool)>,
+    ) -> bool {
+        let success = Rc::new(Cell::new(false));
+        let result = success.clone();
+        let operation = request(
+            &mut self.context,
+            Some(Box::new(move |value| result.set(value))),
+        );
+        self.wait_for(operation) && success.get()
+    }
+}
+
+impl Drop for PulseBackend {
+    fn drop(&mut self) {
+        // the subscription callback holds an introspector, which keeps the context alive
+        self.context.set_subscribe_callback(None);
+        self.requests.borrow_mut().clear();
+        self.context.disconnect();
+    }
+}
+
+impl AudioBackend for PulseBackend {
+    fn server_info(&mut self) -> Option<ServerInfo> {
+        let info = Rc::new(RefCell::new(None));
+        let result = info.clone();
+        let operation = self
+            .context
+            .introspect()
+            .get_server_info(move |server| *result.borrow_mut() = Some(ServerInfo::from(server)));
+        self.wait_for(operation);
+        info.take()
+    }
+
+    fn sinks(&mut self) -> Vec<Sink> {
+        let sinks = Rc::new(RefCell::new(Vec::new()));
+        let result = sinks.clone();
+        let operation = self.context.introspect().get_sink_info_list(move |item| {
+            if let ListResult::Item(info) = item {
+                result.borrow_mut().push(Sink::from(info));
+            }
+        });
+        self.wait_for(operation);
+        sinks.take()
+    }
+
+    fn sources(&mut self) -> Vec<Source> {
+        let sources = Rc::new(RefCell::new(Vec::new()));
+        let result = sources.clone();
+        let operation = self.context.introspect().get_source_info_list(move |item| {
+            if let ListResult::Item(info) = item {
+                result.borrow_mut().push(Source::from(info));
+            }
+        });
+        self.wait_for(operation);
+        sources.take()
+    }
+
+    fn cards(&mut self) -> Vec<Card> {
+        let cards = Rc::new(RefCell::new(Vec::new()));
+        let result = cards.clone();
+        let operation = self.context.introspect().get_card_info_list(move |item| {
+            if let ListResult::Item(info) = item {
+                result.borrow_mut().push(Card::from(info));
+            }
+        });
+        self.wait_for(operation);
+        cards.take()
+    }
+
+    fn input_streams(&mut self) -> Vec<InputStream> {
+        let streams = Rc::new(RefCell::new(Vec::new()));
+        let result = streams.clone();
+        let operation = self
+            .context
+            .introspect()
+            .get_sink_input_info_list(move |item| {
+                if let ListResult::Item(info) = item {
+                    result.borrow_mut().push(InputStream::from(info));
+                }
+            });
+        self.wait_for(operation);
+        streams.take()
+    }
+
+    fn output_streams(&mut self) -> Vec<OutputStream> {
+        let streams = Rc::new(RefCell::new(Vec::new()));
+        let result = streams.clone();
+        let operation = self
+            .context
+            .introspect()
+            .get_source_output_info_list(move |item| {
+                if let ListResult::Item(info) = item {
+                    result.borrow_mut().push(OutputStream::from(info));
+                }
+            });
+        self.wait_for(operation);
+        streams.take()
+    }
+
+    fn subscribe(&mut self) -> bool {
+        let defaults = self
+            .server_info()
+            .map(|info| (info.default_sink, info.default_source))
+            .unwrap_or_default();
+        let known = KnownObjects {
+            sinks: self.sinks().into_iter().map(|x| (x.index, x)).collect(),
+            sources: self.sources().into_iter().map(|x| (x.index, x)).collect(),
+            input_streams: self
+                .input_streams()
+                .into_iter()
+                .map(|x| (x.index, x))
+                .collect(),
+            output_streams: self
+                .output_streams()
+                .into_iter()
+                .map(|x| (x.index, x))
+                .collect(),
+        };
+        let subscription = Subscription {
+            introspector: self.context.introspect(),
+            events: self.events.clone(),
+            defaults: Rc::new(RefCell::new(defaults)),
+            known: Rc::new(RefCell::new(known)),
+            requests: self.requests.clone(),
+        };
+        self.context
+            .set_subscribe_callback(Some(Box::new(move |facility, operation, index| {
+                subscription.handle_event(facility, operation, index);
+            })));
+        let mask = InterestMaskSet::SINK
+            | InterestMaskSet::SOURCE
+            | InterestMaskSet::SINK_INPUT
+            | InterestMaskSet::SOURCE_OUTPUT
+            | InterestMaskSet::SERVER;
+        let success = Rc::new(Cell::new(false));
+        let result = success.clone();
+        let operation = self.context.subscribe(mask, move |value| result.set(value));
+        self.wait_for(operation) && success.get()
+    }
+
+    /// Dispatches the pending subscription events and waits for the info requests they
+    /// started, so added and changed objects are returned by the same call.
+    /// Changed events are held back for [`super::audio_changes::DEFAULT_COALESCE_WINDOW`] to
+    /// merge bursts, they are returned by a later call once the window has passed.
+    fn take_events(&mut self) -> Vec<AudioEvent> {
+        while let IterateResult::Success(dispatched) = self.mainloop.iterate(false) {
+            if dispatched == 0 {
+                break;
+            }
+        }
+        loop {
+            self.requests
+                .borrow_mut()
+                .retain(|state| state() == OperationState::Running);
+            if self.requests.borrow().is_empty() {
+                break;
+            }
+            if let IterateResult::Quit(_) | IterateResult::Err(_) = self.mainloop.iterate(true) {
+                self.requests.borrow_mut().clear();
+                break;
+            }
+        }
+        self.events.borrow_mut().take()
+    }
+
+    fn set_sink_volume(&mut self, index: u32, volume: &ChannelVolumes) -> bool {
+        self.wait_for_success(|context, callback| {
+            context
+                .introspect()
+                .set_sink_volume_by_index(index, &volume.into(), callback)
+        })
+    }
+
+    fn set_sink_mute(&mut self, index: u32, muted: bool) -> bool {
+        self.wait_for_success(|context, callback| {
+            context
+                .introspect()
+                .set_sink_mute_by_index(index, muted, callback)
+        })
+    }
+
+    fn set_default_sink(&mut self, name: &str) -> bool {
+        self.wait_for_success(|context, mut callback| {
+            context.set_default_sink(name, move |success| {
+                if let Some(callback) = callback.as_mut() {
+                    callback(success);
+                }
+            })
+        })
+    }
+
+    fn set_source_volume(&mut self, index: u32, volume: &ChannelVolumes) -> bool {
+        self.wait_for_success(|context, callback| {
+            context
+                .introspect()
+                .set_source_volume_by_index(index, &volume.into(), callback)
+        })
+    }
+
+    fn set_source_mute(&mut self, index: u32, muted: bool) -> bool {
+        self.wait_for_success(|context, callback| {
+            context
+                .introspect()
+                .set_source_mute_by_index(index, muted, callback)
+        })
+    }
+
+    fn set_default_source(&mut self, name: &str) -> bool {
+        self.wait_for_success(|context, mut callback| {
+            context.set_default_source(name, move |success| {
+                if let Some(callback) = callback.as_mut() {
+                    callback(success);
+                }
+            })
+        })
+    }
+
+    fn set_card_profile(&mut self, index: u32, profile: &str) -> bool {
+        self.wait_for_success(|context, callback| {
+            context
+                .introspect()
+                .set_card_profile_by_index(index, profile, callback)
+        })
+    }
+
+    fn set_port_latency_offset(&mut self, offset: &LatencyOffset) -> bool {
+        self.wait_for_success(|context, callback| {
+            context.introspect().set_port_latency_offset(
+                &offset.card_name,
+                &offset.port,
+                offset.offset,
+                callback,
+            )
+        })
+    }
+
+    fn set_input_stream_volume(&mut self, index: u32, volume: &ChannelVolumes) -> bool {
+        self.wait_for_success(|context, callback| {
+            context
+                .introspect()
+                .set_sink_input_volume(index, &volume.into(), callback)
+        })
+    }
+
+    fn set_input_stream_mute(&mut self, index: u32, muted: bool) -> bool {
+        self.wait_for_success(|context, callback| {
+            context
+                .introspect()
+                .set_sink_input_mute(index, muted, callback)
+        })
+    }
+
+    fn move_input_stream(&mut self, index: u32, sink_index: u32) -> bool {
+        self.wait_for_success(|context, callback| {
+            context
+                .introspect()
+                .move_sink_input_by_index(index, sink_index, callback)
+        })
+    }
+
+    fn set_output_stream_volume(&mut self, index: u32, volume: &ChannelVolumes) -> bool {
+        self.wait_for_success(|context, callback| {
+            context
+                .introspect()
+                .set_source_output_volume(index, &volume.into(), callback)
+        })
+    }
+
+    fn set_output_stream_mute(&mut self, index: u32, muted: bool) -> bool {
+        self.wait_for_success(|context, callback| {
+            context
+                .introspect()
+                .set_source_output_mute(index, muted, callback)
+        })
+    }
+
+    fn move_output_stream(&mut self, index: u32, source_index: u32) -> bool {
+        self.wait_for_success(|context, callback| {
+            context
+                .introspect()
+                .move_source_output_by_index(index, source_index, callback)
+        })
+    }
+}
+
+struct Subscription {
+    introspector: Introspector,
+    events: Rc<RefCell<ChangeCoalescer>>,
+    /// Default sink and source, to only report actual changes.
+    defaults: Rc<RefCell<(String, String)>>,
+    known: Rc<RefCell<KnownObjects>>,
+    requests: PendingRequests,
+}
+
+impl Subscription {
+    /// Turns a pulse subscription event into [`AudioEvent`]s.
+    /// New and changed objects are fetched first, the event is queued once the info arrives and
+    /// the request is added to `requests`.
+    /// Changed events are dropped if none of the fields of the object changed.
+    fn handle_event(
+        &self,
+        facility: Option<Facility>,
+        operation: Option<SubscribeOperation>,
+        index: u32,
+    ) {
+        let Some(operation) = operation else {
+            return;
+        };
+        let added = operation == SubscribeOperation::New;
+        let introspector = &self.introspector;
+        let requests = &self.requests;
+        let events = self.events.clone();
+        let known = self.known.clone();
+        match (facility, operation) {
+            (Some(Facility::Sink), SubscribeOperation::Removed) => {
+                known.borrow_mut().sinks.remove(&index);
+                events
+                    .borrow_mut()
+                    .push(AudioEvent::SinkRemoved(SinkRemoved { index }))
+            }
+            (Some(Facility::Source), SubscribeOperation::Removed) => {
+                known.borrow_mut().sources.remove(&index);
+                events
+                    .borrow_mut()
+                    .push(AudioEvent::SourceRemoved(SourceRemoved { index }))
+            }
+            (Some(Facility::SinkInput), SubscribeOperation::Removed) => {
+                known.borrow_mut().input_streams.remove(&index);
+                events
+                    .borrow_mut()
+                    .push(AudioEvent::InputStreamRemoved(InputStreamRemoved { index }))
+            }
+            (Some(Facility::SourceOutput), SubscribeOperation::Removed) => {
+                known.borrow_mut().output_streams.remove(&index);
+                events
+                    .borrow_mut()
+                    .push(AudioEvent::OutputStreamRemoved(OutputStreamRemoved {
+                        index,
+                    }))
+            }
+            (Some(Facility::Sink), _) => {
+                let operation = introspector.get_sink_info_by_index(index, move |item| {
+                    if let ListResult::Item(info) = item {
+                        let sink = Sink::from(info);
+                        let changes = update_known(
+                            &mut known.borrow_mut().sinks,
+                            index,
+                            &sink,
+                            Sink::changes,
+                        );
+                        if added {
+                            events
+                                .borrow_mut()
+                                .push(AudioEvent::SinkAdded(SinkAdded { sink }));
+                        } else if let Some(changes) = changes {
+                            events
+                                .borrow_mut()
+                                .push(AudioEvent::SinkChanged(SinkChanged { sink, changes }));
+                        }
+                    }
+                });
+                track(requests, operation);
+            }
+            (Some(Facility::Source), _) => {
+                let operation = introspector.get_source_info_by_index(index, move |item| {
+                    if let ListResult::Item(info) = item {
+                        let source = Source::from(info);
+                        let changes = update_known(
+                            &mut known.borrow_mut().sources,
+                            index,
+                            &source,
+                            Source::changes,
+                        );
+                        if added {
+                            events
+                                .borrow_mut()
+                                .push(AudioEvent::SourceAdded(SourceAdded { source }));
+                        } else if let Some(changes) = changes {
+                            events
+                                .borrow_mut()
+                                .push(AudioEvent::SourceChanged(SourceChanged { source, changes }));
+                        }
+                    }
+                });
+                track(requests, operation);
+            }
+            (Some(Facility::SinkInput), _) => {
+                let operation = introspector.get_sink_input_info(index, move |item| {
+                    if let ListResult::Item(info) = item {
+                        let stream = InputStream::from(info);
+                        let changes = update_known(
+                            &mut known.borrow_mut().input_streams,
+                            index,
+                            &stream,
+                            InputStream::changes,
+                        );
+                        if added {
+                            events
+                                .borrow_mut()
+                                .push(AudioEvent::InputStreamAdded(InputStreamAdded { stream }));
+                        } else if let Some(changes) = changes {
+                            events.borrow_mut().push(AudioEvent::InputStreamChanged(
+                                InputStreamChanged { stream, changes },
+                            ));
+                        }
+                    }
+                });
+                track(requests, operation);
+            }
+            (Some(Facility::SourceOutput), _) => {
+                let operation = introspector.get_source_output_info(index, move |item| {
+                    if let ListResult::Item(info) = item {
+                        let stream = OutputStream::from(info);
+                        let changes = update_known(
+                            &mut known.borrow_mut().output_streams,
+                            index,
+                            &stream,
+                            OutputStream::changes,
+                        );
+                        if added {
+                            events
+                                .borrow_mut()
+                                .push(AudioEvent::OutputStreamAdded(OutputStreamAdded { stream }));
+                        } else if let Some(changes) = changes {
+                            events.borrow_mut().push(AudioEvent::OutputStreamChanged(
+                                OutputStreamChanged { stream, changes },
+                            ));
+                        }
+                    }
+                });
+                track(requests, operation);
+            }
+            (Some(Facility::Server), _) => {
+                let defaults = self.defaults.clone();
+                let operation = introspector.get_server_info(move |info| {
+                    let info = ServerInfo::from(info);
+                    let mut defaults = defaults.borrow_mut();
+                    if defaults.0 != info.default_sink {
+                        defaults.0.clone_from(&info.default_sink);
+                        events.borrow_mut().push(AudioEvent::DefaultSinkChanged(
+                            DefaultSinkChanged {
+                                name: info.default_sink,
+                            },
+                        ));
+                    }
+                    if defaults.1 != info.default_source {
+                        defaults.1.clone_from(&info.default_source);
+                        events.borrow_mut().push(AudioEvent::DefaultSourceChanged(
+                            DefaultSourceChanged {
+                                name: info.default_source,
+                            },
+                        ));
+                    }
+                });
+                track(requests, operation);
+            }
+            _ => (),
+        }
+    }
+}
+
+fn track<T: ?Sized + 'static>(requests: &PendingRequests, operation: Operation<T>) {
+    requests
+        .borrow_mut()
+        .push(Box::new(move || operation.get_state()));
+}
@@ -0,0 +1,315 @@
+use toml::{Table, Value};
+
+use crate::signals::{InputStreamAdded, OutputStreamAdded};
+use crate::utils::config::{parse_config, remove_config_value, write_config_value};
+
+use super::audio_structures::{ChannelVolumes, TAudioStreamObject, Volume};
+
+pub const STREAM_RULE_CATEGORY: &str = "StreamRules";
+
+/// Preferred device, volume and mute state for the streams of an application.
+/// Unset criteria match every stream, but a rule needs at least one criterion to match
+/// anything. Unset settings are left untouched.
+///
+/// ```toml
+/// [StreamRules.discord]
+/// application_name = "Discord"
+/// sink = "alsa_output.usb-headset"
+/// volume = 60
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StreamRule {
+    pub name: String,
+    /// Compared case insensitively.
+    pub application_name: Option<String>,
+    pub process_binary: Option<String>,
+    pub media_role: Option<String>,
+    /// Sink for playback streams.
+    pub sink: Option<String>,
+    /// Source for recording streams.
+    pub source: Option<String>,
+    pub volume: Option<Volume>,
+    pub muted: Option<bool>,
+    /// Breaks ties between equally specific rules, the higher priority wins.
+    pub priority: i64,
+}
+
+impl StreamRule {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: String::from(name),
+            ..Default::default()
+        }
+    }
+
+    /// Rules without criteria never match, so an empty rule can not route every stream.
+    pub fn matches(&self, stream: &impl TAudioStreamObject) -> bool {
+        if self.specificity() == 0 {
+            return false;
+        }
+        if let Some(application_name) = &self.application_name {
+            if !application_name.eq_ignore_ascii_case(&stream.application_name()) {
+                return false;
+            }
+        }
+        if let Some(process_binary) = &self.process_binary {
+            if *process_binary != stream.process_binary() {
+                return false;
+            }
+        }
+        if let Some(media_role) = &self.media_role {
+            if *media_role != stream.media_role() {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Number of criteria, rules with more criteria take precedence.
+    pub fn specificity(&self) -> usize {
+        [
+            self.application_name.is_some(),
+            self.process_binary.is_some(),
+            self.media_role.is_some(),
+        ]
+        .iter()
+        .filter(|criterion| **criterion)
+        .count()
+    }
+
+    /// Stream volume with the rule volume applied to all channels.
+    pub fn apply_volume(&self, volume: &ChannelVolumes) -> ChannelVolumes {
+        let mut volume = volume.clone();
+        if let Some(rule_volume) = self.volume {
+            volume.set_all(rule_volume);
+        }
+        volume
+    }
+
+    pub fn to_toml(&self) -> Table {
+        let mut table = Table::new();
+        let strings = [
+            ("application_name", &self.application_name),
+            ("process_binary", &self.process_binary),
+            ("media_role", &self.media_role),
+            ("sink", &self.sink),
+            ("source", &self.source),
+        ];
+        for (key, value) in strings {
+            if let Some(value) = value {
+                table.insert(key.into(), Value::String(value.clone()));
+            }
+        }
+        if let Some(volume) = self.volume {
+            table.insert("volume".into(), Value::Float(volume.percent()));
+        }
+        if let Some(muted) = self.muted {
+            table.insert("muted".into(), Value::Boolean(muted));
+        }
+        if self.priority != 0 {
+            table.insert("priority".into(), Value::Integer(self.priority));
+        }
+        table
+    }
+
+    /// Volumes are stored in percent and may be written as integer or float.
+    pub fn from_toml(name: &str, table: &Table) -> Self {
+        let string = |key: &str| table.get(key).and_then(Value::as_str).map(String::from);
+        let volume = match table.get("volume") {
+            Some(Value::Float(volume)) => Some(Volume::from_percent(*volume)),
+            Some(Value::Integer(volume)) => Some(Volume::from_percent(*volume as f64)),
+            _ => None,
+        };
+        Self {
+            name: String::from(name),
+            application_name: string("application_name"),
+            process_binary: string("process_binary"),
+            media_role: string("media_role"),
+            sink: string("sink"),
+            source: string("source"),
+            volume,
+            muted: table.get("muted").and_then(Value::as_bool),
+            priority: table
+                .get("priority")
+                .and_then(Value::as_integer)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Rules without criteria are refused.
+    pub fn save(&self) -> bool {
+        if self.specificity() == 0 {
+            return false;
+        }
+        write_config_value(
+            STREAM_RULE_CATEGORY,
+            &self.name,
+            Value::Table(self.to_toml()),
+        )
+    }
+
+    pub fn remove(&self) -> bool {
+        remove_config_value(STREAM_RULE_CATEGORY, &self.name)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StreamRules {
+    pub rules: Vec<StreamRule>,
+}
+
+impl StreamRules {
+    pub fn load() -> Self {
+        Self::from_toml(&parse_config())
+    }
+
+    pub fn from_toml(config: &Table) -> Self {
+        let mut rules = Vec::new();
+        if let Some(Value::Table(category)) = config.get(STREAM_RULE_CATEGORY) {
+            for (name, rule) in category.iter() {
+                if let Value::Table(rule) = rule {
+                    rules.push(StreamRule::from_toml(name, rule));
+                }
+            }
+        }
+        Self { rules }
+    }
+
+    /// Most specific rule for the stream, ties are broken by [`StreamRule::priority`].
+    /// Rules with equal specificity and priority fall back to the first rule in `rules`,
+    /// which is the alphabetically first name for rules read from the config.
+    pub fn matching(&self, stream: &impl TAudioStreamObject) -> Option<&StreamRule> {
+        let mut result: Option<&StreamRule> = None;
+        for rule in self.rules.iter().filter(|rule| rule.matches(stream)) {
+            let preferred = match result {
+                Some(best) => {
+                    (rule.specificity(), rule.priority) > (best.specificity(), best.priority)
+                }
+                None => true,
+            };
+            if preferred {
+                result = Some(rule);
+            }
+        }
+        result
+    }
+
+    /// Rule for a new playback stream, use [`StreamRule::sink`] as the target.
+    pub fn for_input_stream(&self, event: &InputStreamAdded) -> Option<&StreamRule> {
+        self.matching(&event.stream)
+    }
+
+    /// Rule for a new recording stream, use [`StreamRule::source`] as the target.
+    pub fn for_output_stream(&self, event: &OutputStreamAdded) -> Option<&StreamRule> {
+        self.matching(&event.stream)
+    }
+}
+
+#[test]
+fn stream_rules_test() {
+    use super::audio_structures::{InputStream, StreamMetadata};
+
+    let config = r#"
+        [StreamRules.discord]
+        application_name = "discord"
+        sink = "alsa_output.usb-headset"
+        volume = 60
+
+        [StreamRules.discord_notifications]
+        application_name = "Discord"
+        media_role = "event"
+        muted = true
+
+        [StreamRules.firefox]
+        process_binary = "firefox"
+        volume = 80.5
+
+        [StreamRules.everything]
+        sink = "alsa_output.hdmi"
+
+        [StreamRules.spotify_headset]
+        application_name = "Spotify"
+        sink = "alsa_output.usb-headset"
+
+        [StreamRules.spotify_speakers]
+        process_binary = "spotify"
+        sink = "alsa_output.speakers"
+    "#
+    .parse::<Table>()
+    .unwrap();
+    let rules = StreamRules::from_toml(&config);
+    assert_eq!(rules.rules.len(), 6);
+
+    let event = InputStreamAdded {
+        stream: InputStream {
+            application_name: String::from("Discord"),
+            volume: ChannelVolumes::from_raw(&[65536, 65536]),
+            ..Default::default()
+        },
+    };
+    let rule = rules.for_input_stream(&event).unwrap();
+    assert_eq!(rule.name, "discord");
+    assert_eq!(rule.sink.as_deref(), Some("alsa_output.usb-headset"));
+    assert_eq!(
+        rule.apply_volume(&event.stream.volume),
+        ChannelVolumes::new(2, Volume::from_percent(60.0))
+    );
+
+    let event = InputStreamAdded {
+        stream: InputStream {
+            application_name: String::from("Discord"),
+            metadata: StreamMetadata {
+                media_role: String::from("event"),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    };
+    assert_eq!(
+        rules.for_input_stream(&event).unwrap().name,
+        "discord_notifications"
+    );
+
+    let event = InputStreamAdded {
+        stream: InputStream {
+            application_name: String::from("Spotify"),
+            metadata: StreamMetadata {
+                process_binary: String::from("spotify"),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    };
+    assert_eq!(
+        rules.for_input_stream(&event).unwrap().name,
+        "spotify_headset"
+    );
+    let mut prioritized = rules.clone();
+    prioritized
+        .rules
+        .iter_mut()
+        .find(|rule| rule.name == "spotify_speakers")
+        .unwrap()
+        .priority = 1;
+    assert_eq!(
+        prioritized.for_input_stream(&event).unwrap().name,
+        "spotify_speakers"
+    );
+
+    let event = InputStreamAdded {
+        stream: InputStream {
+            application_name: String::from("Slack"),
+            ..Default::default()
+        },
+    };
+    assert!(rules.for_input_stream(&event).is_none());
+    assert!(!StreamRule::new("everything").save());
+
+    let firefox = rules
+        .rules
+        .iter()
+        .find(|rule| rule.name == "firefox")
+        .unwrap();
+    let parsed = StreamRule::from_toml("firefox", &firefox.to_toml());
+    assert_eq!(&parsed, firefox);
+}
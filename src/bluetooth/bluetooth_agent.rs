@@ -0,0 +1,495 @@
+use std::{
+    fmt::Display,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use dbus::{blocking::Connection, channel::Sender, message::SignalArgs, Message, Path};
+use dbus_crossroads::{Context, Crossroads, IfaceBuilder, IfaceToken, MethodErr};
+
+use crate::{
+    signals::{
+        BluetoothAuthorizationRequested, BluetoothConfirmationRequested, BluetoothPairingCanceled,
+        BluetoothPasskeyDisplayed, BluetoothPasskeyRequested, BluetoothPinDisplayed,
+        BluetoothPinRequested,
+    },
+    utils::dbus_utils::DBUS_PATH,
+};
+
+pub const BLUEZ_AGENT_INTERFACE: &str = "org.bluez.Agent1";
+pub const BLUEZ_AGENT_MANAGER_INTERFACE: &str = "org.bluez.AgentManager1";
+const REJECTED: &str = "org.bluez.Error.Rejected";
+
+/// Input and output capabilities announced to BlueZ, decides which pairing methods are used.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AgentCapability {
+    DisplayOnly,
+    DisplayYesNo,
+    KeyboardOnly,
+    NoInputNoOutput,
+    #[default]
+    KeyboardDisplay,
+}
+
+impl Display for AgentCapability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AgentCapability::DisplayOnly => f.write_str("DisplayOnly"),
+            AgentCapability::DisplayYesNo => f.write_str("DisplayYesNo"),
+            AgentCapability::KeyboardOnly => f.write_str("KeyboardOnly"),
+            AgentCapability::NoInputNoOutput => f.write_str("NoInputNoOutput"),
+            AgentCapability::KeyboardDisplay => f.write_str("KeyboardDisplay"),
+        }
+    }
+}
+
+/// Callbacks of the `org.bluez.Agent1` interface.
+/// Requests are only announced here, the answer is given later with the [`AgentReplies`] of
+/// the [`PairingAgent`], so the D-Bus connection is never blocked while waiting for the user.
+pub trait BluetoothAgent: Send + 'static {
+    /// Answer with [`AgentReplies::reply_pin_code`].
+    fn request_pin_code(&mut self, device: Path<'static>);
+    /// Answer with [`AgentReplies::reply_passkey`].
+    fn request_passkey(&mut self, device: Path<'static>);
+    fn display_pin_code(&mut self, device: Path<'static>, pin_code: String);
+    /// Called again for every digit typed on the device.
+    fn display_passkey(&mut self, device: Path<'static>, passkey: u32, entered: u16);
+    /// Answer with [`AgentReplies::confirm`] or [`AgentReplies::reject`].
+    fn request_confirmation(&mut self, device: Path<'static>, passkey: u32);
+    /// Pairing request without a passkey, answer with [`AgentReplies::confirm`] or
+    /// [`AgentReplies::reject`].
+    fn request_authorization(&mut self, device: Path<'static>);
+    /// Answer with [`AgentReplies::confirm`] or [`AgentReplies::reject`].
+    fn authorize_service(&mut self, device: Path<'static>, uuid: String);
+    /// BlueZ canceled the pending request of `device`, e.g. after a timeout.
+    fn cancel(&mut self, device: Path<'static>);
+    /// The agent was unregistered by BlueZ.
+    fn release(&mut self) {}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RequestKind {
+    PinCode,
+    Passkey,
+    Confirmation,
+}
+
+#[derive(Debug)]
+struct PendingRequest {
+    context: Context,
+    kind: RequestKind,
+    device: Path<'static>,
+}
+
+/// Handle to answer the request BlueZ is waiting for, can be cloned into the method handlers
+/// of the daemon.
+/// The replies have to be sent on the system bus connection the agent is registered on.
+#[derive(Debug, Clone, Default)]
+pub struct AgentReplies(Arc<Mutex<Option<PendingRequest>>>);
+
+impl AgentReplies {
+    pub fn pending_device(&self) -> Option<Path<'static>> {
+        let pending = self.0.lock().ok()?;
+        pending.as_ref().map(|request| request.device.clone())
+    }
+
+    pub fn reply_pin_code<S: Sender + ?Sized>(&self, pin_code: String, conn: &S) -> bool {
+        self.reply(RequestKind::PinCode, conn, |context| {
+            context.reply(Ok((pin_code,)));
+        })
+    }
+
+    pub fn reply_passkey<S: Sender + ?Sized>(&self, passkey: u32, conn: &S) -> bool {
+        self.reply(RequestKind::Passkey, conn, |context| {
+            context.reply(Ok((passkey,)));
+        })
+    }
+
+    /// Accepts a confirmation, authorization or service request.
+    pub fn confirm<S: Sender + ?Sized>(&self, conn: &S) -> bool {
+        self.reply(RequestKind::Confirmation, conn, |context| {
+            context.reply(Ok(()));
+        })
+    }
+
+    pub fn reject<S: Sender + ?Sized>(&self, conn: &S) -> bool {
+        let Some(mut request) = self.take(None) else {
+            return false;
+        };
+        request
+            .context
+            .reply::<()>(Err(MethodErr::from((REJECTED, "Rejected by the user"))));
+        request.context.flush_messages(conn).is_ok()
+    }
+
+    fn reply<S: Sender + ?Sized>(
+        &self,
+        kind: RequestKind,
+        conn: &S,
+        reply: impl FnOnce(&mut Context),
+    ) -> bool {
+        let Some(mut request) = self.take(Some(kind)) else {
+            return false;
+        };
+        reply(&mut request.context);
+        request.context.flush_messages(conn).is_ok()
+    }
+
+    /// Removes the pending request if it is of `kind`, any kind if None.
+    fn take(&self, kind: Option<RequestKind>) -> Option<PendingRequest> {
+        let mut pending = self.0.lock().ok()?;
+        match pending.as_ref() {
+            Some(request) if kind.is_none() || kind == Some(request.kind) => pending.take(),
+            _ => None,
+        }
+    }
+
+    /// Stores the request, returns the context again if another request is still waiting.
+    fn set(&self, context: Context, kind: RequestKind, device: Path<'static>) -> Option<Context> {
+        let Ok(mut pending) = self.0.lock() else {
+            return Some(context);
+        };
+        if pending.is_some() {
+            return Some(context);
+        }
+        *pending = Some(PendingRequest {
+            context,
+            kind,
+            device,
+        });
+        None
+    }
+}
+
+/// Object data of the agent interface, insert it with the token of
+/// [`register_agent_interface`].
+pub struct PairingAgent<T: BluetoothAgent> {
+    pub agent: T,
+    replies: AgentReplies,
+}
+
+impl<T: BluetoothAgent> PairingAgent<T> {
+    pub fn new(agent: T) -> Self {
+        Self {
+            agent,
+            replies: AgentReplies::default(),
+        }
+    }
+
+    pub fn replies(&self) -> AgentReplies {
+        self.replies.clone()
+    }
+}
+
+/// Registers `org.bluez.Agent1` for objects holding a [`PairingAgent`].
+/// Only one request is handled at a time, further requests are rejected until it is answered
+/// or canceled.
+pub fn register_agent_interface<T: BluetoothAgent>(
+    cross: &mut Crossroads,
+) -> IfaceToken<PairingAgent<T>> {
+    cross.register(
+        BLUEZ_AGENT_INTERFACE,
+        |builder: &mut IfaceBuilder<PairingAgent<T>>| {
+            builder.method("Release", (), (), |_, data: &mut PairingAgent<T>, ()| {
+                data.agent.release();
+                Ok(())
+            });
+            builder.method_with_cr_custom::<(Path<'static>,), (String,), _, _>(
+                "RequestPinCode",
+                ("device",),
+                ("pincode",),
+                |context, cross, (device,)| {
+                    defer_reply(
+                        context,
+                        cross,
+                        RequestKind::PinCode,
+                        device,
+                        |agent: &mut T, device| agent.request_pin_code(device),
+                    )
+                },
+            );
+            builder.method(
+                "DisplayPinCode",
+                ("device", "pincode"),
+                (),
+                |_, data: &mut PairingAgent<T>, (device, pin_code): (Path<'static>, String)| {
+                    data.agent.display_pin_code(device, pin_code);
+                    Ok(())
+                },
+            );
+            builder.method_with_cr_custom::<(Path<'static>,), (u32,), _, _>(
+                "RequestPasskey",
+                ("device",),
+                ("passkey",),
+                |context, cross, (device,)| {
+                    defer_reply(
+                        context,
+                        cross,
+                        RequestKind::Passkey,
+                        device,
+                        |agent: &mut T, device| agent.request_passkey(device),
+                    )
+                },
+            );
+            builder.method(
+                "DisplayPasskey",
+                ("device", "passkey", "entered"),
+                (),
+                |_,
+                 data: &mut PairingAgent<T>,
+                 (device, passkey, entered): (Path<'static>, u32, u16)| {
+                    data.agent.display_passkey(device, passkey, entered);
+                    Ok(())
+                },
+            );
+            builder.method_with_cr_custom::<(Path<'static>, u32), (), _, _>(
+                "RequestConfirmation",
+                ("device", "passkey"),
+                (),
+                |context, cross, (device, passkey)| {
+                    defer_reply(
+                        context,
+                        cross,
+                        RequestKind::Confirmation,
+                        device,
+                        |agent: &mut T, device| agent.request_confirmation(device, passkey),
+                    )
+                },
+            );
+            builder.method_with_cr_custom::<(Path<'static>,), (), _, _>(
+                "RequestAuthorization",
+                ("device",),
+                (),
+                |context, cross, (device,)| {
+                    defer_reply(
+                        context,
+                        cross,
+                        RequestKind::Confirmation,
+                        device,
+                        |agent: &mut T, device| agent.request_authorization(device),
+                    )
+                },
+            );
+            builder.method_with_cr_custom::<(Path<'static>, String), (), _, _>(
+                "AuthorizeService",
+                ("device", "uuid"),
+                (),
+                |context, cross, (device, uuid)| {
+                    defer_reply(
+                        context,
+                        cross,
+                        RequestKind::Confirmation,
+                        device,
+                        |agent: &mut T, device| agent.authorize_service(device, uuid),
+                    )
+                },
+            );
+            builder.method("Cancel", (), (), |_, data: &mut PairingAgent<T>, ()| {
+                // BlueZ does not expect a reply to the canceled request anymore
+                if let Some(request) = data.replies.take(None) {
+                    data.agent.cancel(request.device);
+                }
+                Ok(())
+            });
+        },
+    )
+}
+
+/// Stores the request until it is answered with [`AgentReplies`], returns the context to
+/// crossroads if it has to be answered right away.
+fn defer_reply<T: BluetoothAgent>(
+    mut context: Context,
+    cross: &mut Crossroads,
+    kind: RequestKind,
+    device: Path<'static>,
+    notify: impl FnOnce(&mut T, Path<'static>),
+) -> Option<Context> {
+    let Some(data) = cross.data_mut::<PairingAgent<T>>(context.path()) else {
+        let error = MethodErr::no_path(context.path());
+        context.reply::<()>(Err(error));
+        return Some(context);
+    };
+    match data.replies.set(context, kind, device.clone()) {
+        None => {
+            notify(&mut data.agent, device);
+            None
+        }
+        Some(mut context) => {
+            context.reply::<()>(Err(MethodErr::from((
+                REJECTED,
+                "Another pairing request is in progress",
+            ))));
+            Some(context)
+        }
+    }
+}
+
+/// `conn` has to be the connection the agent interface is served on.
+pub fn register_agent(
+    conn: &Connection,
+    path: Path<'static>,
+    capability: AgentCapability,
+    default_agent: bool,
+) -> Result<(), dbus::Error> {
+    let proxy = conn.with_proxy("org.bluez", "/org/bluez", Duration::from_millis(1000));
+    proxy.method_call::<(), _, _, _>(
+        BLUEZ_AGENT_MANAGER_INTERFACE,
+        "RegisterAgent",
+        (path.clone(), capability.to_string()),
+    )?;
+    if default_agent {
+        proxy.method_call::<(), _, _, _>(
+            BLUEZ_AGENT_MANAGER_INTERFACE,
+            "RequestDefaultAgent",
+            (path,),
+        )?;
+    }
+    Ok(())
+}
+
+pub fn unregister_agent(conn: &Connection, path: Path<'static>) -> Result<(), dbus::Error> {
+    let proxy = conn.with_proxy("org.bluez", "/org/bluez", Duration::from_millis(1000));
+    proxy.method_call(BLUEZ_AGENT_MANAGER_INTERFACE, "UnregisterAgent", (path,))
+}
+
+/// [`BluetoothAgent`] that forwards every callback as ReSet signal, e.g.
+/// [`BluetoothPinRequested`], the frontend then answers with the reply methods of the daemon.
+pub struct SignalAgent {
+    emit: Box<dyn FnMut(Message) + Send>,
+}
+
+impl SignalAgent {
+    /// `emit` sends the signal messages, usually on the session bus connection of the daemon.
+    pub fn new(emit: impl FnMut(Message) + Send + 'static) -> Self {
+        Self {
+            emit: Box::new(emit),
+        }
+    }
+
+    fn emit(&mut self, signal: impl SignalArgs + dbus::arg::AppendAll) {
+        (self.emit)(signal.to_emit_message(&Path::from(DBUS_PATH)));
+    }
+}
+
+impl BluetoothAgent for SignalAgent {
+    fn request_pin_code(&mut self, device: Path<'static>) {
+        self.emit(BluetoothPinRequested { device });
+    }
+
+    fn request_passkey(&mut self, device: Path<'static>) {
+        self.emit(BluetoothPasskeyRequested { device });
+    }
+
+    fn display_pin_code(&mut self, device: Path<'static>, pin_code: String) {
+        self.emit(BluetoothPinDisplayed { device, pin_code });
+    }
+
+    fn display_passkey(&mut self, device: Path<'static>, passkey: u32, entered: u16) {
+        self.emit(BluetoothPasskeyDisplayed {
+            device,
+            passkey,
+            entered,
+        });
+    }
+
+    fn request_confirmation(&mut self, device: Path<'static>, passkey: u32) {
+        self.emit(BluetoothConfirmationRequested { device, passkey });
+    }
+
+    fn request_authorization(&mut self, device: Path<'static>) {
+        self.emit(BluetoothAuthorizationRequested {
+            device,
+            uuid: String::new(),
+        });
+    }
+
+    fn authorize_service(&mut self, device: Path<'static>, uuid: String) {
+        self.emit(BluetoothAuthorizationRequested { device, uuid });
+    }
+
+    fn cancel(&mut self, device: Path<'static>) {
+        self.emit(BluetoothPairingCanceled { device });
+    }
+}
+
+#[test]
+fn pairing_agent_test() {
+    use dbus::MessageType;
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    struct Messages(RefCell<Vec<Message>>);
+
+    impl Sender for Messages {
+        fn send(&self, message: Message) -> Result<u32, ()> {
+            self.0.borrow_mut().push(message);
+            Ok(0)
+        }
+    }
+
+    let signals = Arc::new(Mutex::new(Vec::new()));
+    let emitted = signals.clone();
+    let agent = PairingAgent::new(SignalAgent::new(move |message| {
+        emitted.lock().unwrap().push(message)
+    }));
+    let replies = agent.replies();
+    let mut cross = Crossroads::new();
+    let token = register_agent_interface(&mut cross);
+    cross.insert("/org/Xetibo/ReSet/Agent", &[token], agent);
+
+    let device = Path::from("/org/bluez/hci0/dev_00_11_22_33_44_55");
+    let call = |method: &str| {
+        let mut message = Message::new_method_call(
+            ":1.1",
+            "/org/Xetibo/ReSet/Agent",
+            BLUEZ_AGENT_INTERFACE,
+            method,
+        )
+        .unwrap();
+        message.set_serial(1);
+        message
+    };
+    let sent = Messages::default();
+
+    cross
+        .handle_message(
+            call("RequestConfirmation").append2(&device, 123456u32),
+            &sent,
+        )
+        .unwrap();
+    assert!(sent.0.borrow().is_empty());
+    assert_eq!(replies.pending_device(), Some(device.clone()));
+    let signal = signals.lock().unwrap().remove(0);
+    let request: BluetoothConfirmationRequested = signal.read_all().unwrap();
+    assert_eq!(request.passkey, 123456);
+
+    // a second request is rejected while the first one is pending
+    cross
+        .handle_message(call("RequestPinCode").append1(&device), &sent)
+        .unwrap();
+    assert_eq!(sent.0.borrow_mut().remove(0).msg_type(), MessageType::Error);
+
+    assert!(!replies.reply_pin_code(String::from("0000"), &sent));
+    assert!(replies.confirm(&sent));
+    assert_eq!(
+        sent.0.borrow_mut().remove(0).msg_type(),
+        MessageType::MethodReturn
+    );
+    assert!(replies.pending_device().is_none());
+
+    cross
+        .handle_message(call("RequestPasskey").append1(&device), &sent)
+        .unwrap();
+    cross.handle_message(call("Cancel"), &sent).unwrap();
+    assert_eq!(
+        sent.0.borrow_mut().remove(0).msg_type(),
+        MessageType::MethodReturn
+    );
+    assert!(!replies.reject(&sent));
+    let signals = signals.lock().unwrap();
+    assert!(signals[1].read1::<Path>().unwrap() == device);
+    assert_eq!(
+        signals[1].member().unwrap().to_string(),
+        "BluetoothPairingCanceled"
+    );
+}
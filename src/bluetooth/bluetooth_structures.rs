@@ -1,11 +1,22 @@
-use std::any;
+use std::{any, collections::HashMap, fmt::Display, time::Duration};
 
 use dbus::{
-    arg::{self, Append, Arg, ArgType, Get, IterAppend, RefArg},
+    arg::{self, Append, Arg, ArgType, Get, IterAppend, PropMap, RefArg, Variant},
+    blocking::Connection,
     Path, Signature,
 };
 
-#[derive(Debug, Clone, Default)]
+use crate::{
+    network::connection::{Enum, PropMapConvert},
+    signals::PropertiesChanged,
+};
+
+pub const BLUEZ_ADAPTER_INTERFACE: &str = "org.bluez.Adapter1";
+pub const BLUEZ_DEVICE_INTERFACE: &str = "org.bluez.Device1";
+pub const BLUEZ_BATTERY_INTERFACE: &str = "org.bluez.Battery1";
+pub const BLUEZ_MEDIA_CONTROL_INTERFACE: &str = "org.bluez.MediaControl1";
+
+#[derive(Debug, Clone)]
 pub struct BluetoothDevice {
     pub path: Path<'static>,
     pub rssi: i16,
@@ -19,6 +30,46 @@ pub struct BluetoothDevice {
     pub connected: bool,
     pub icon: String,
     pub address: String,
+    /// Advertised service UUIDs, used to find the supported profiles before connecting.
+    pub uuids: Vec<String>,
+    /// Class of device, 0 for low energy devices.
+    pub class: u32,
+    /// GAP appearance, only set for low energy devices.
+    pub appearance: u16,
+    pub modalias: String,
+    pub legacy_pairing: bool,
+    pub tx_power: i16,
+    /// Battery level in percent, -1 if the device does not report one.
+    pub battery_percentage: i16,
+    /// MediaPlayer1 object of the device, `/` if no player is connected.
+    pub media_player: Path<'static>,
+}
+
+impl Default for BluetoothDevice {
+    fn default() -> Self {
+        Self {
+            path: Path::default(),
+            rssi: 0,
+            alias: String::new(),
+            name: String::new(),
+            adapter: Path::default(),
+            trusted: false,
+            bonded: false,
+            paired: false,
+            blocked: false,
+            connected: false,
+            icon: String::new(),
+            address: String::new(),
+            uuids: Vec::new(),
+            class: 0,
+            appearance: 0,
+            modalias: String::new(),
+            legacy_pairing: false,
+            tx_power: 0,
+            battery_percentage: -1,
+            media_player: Path::default(),
+        }
+    }
 }
 
 unsafe impl Send for BluetoothDevice {}
@@ -26,46 +77,29 @@ unsafe impl Sync for BluetoothDevice {}
 
 impl<'a> Get<'a> for BluetoothDevice {
     fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
-        let (
-            path,
-            rssi,
-            name,
-            alias,
-            adapter,
-            trusted,
-            bonded,
-            paired,
-            blocked,
-            connected,
-            icon,
-            address,
-        ) = <(
-            Path<'static>,
-            i16,
-            String,
-            String,
-            Path<'static>,
-            bool,
-            bool,
-            bool,
-            bool,
-            bool,
-            String,
-            String,
-        )>::get(i)?;
+        // more fields than the tuple implementations of dbus support
+        let mut i = i.recurse(ArgType::Struct)?;
         Some(BluetoothDevice {
-            path,
-            rssi,
-            name,
-            alias,
-            adapter,
-            trusted,
-            bonded,
-            paired,
-            blocked,
-            connected,
-            icon,
-            address,
+            path: i.read().ok()?,
+            rssi: i.read().ok()?,
+            name: i.read().ok()?,
+            alias: i.read().ok()?,
+            adapter: i.read().ok()?,
+            trusted: i.read().ok()?,
+            bonded: i.read().ok()?,
+            paired: i.read().ok()?,
+            blocked: i.read().ok()?,
+            connected: i.read().ok()?,
+            icon: i.read().ok()?,
+            address: i.read().ok()?,
+            uuids: i.read().ok()?,
+            class: i.read().ok()?,
+            appearance: i.read().ok()?,
+            modalias: i.read().ok()?,
+            legacy_pairing: i.read().ok()?,
+            tx_power: i.read().ok()?,
+            battery_percentage: i.read().ok()?,
+            media_player: i.read().ok()?,
         })
     }
 }
@@ -85,6 +119,14 @@ impl Append for BluetoothDevice {
             i.append(self.connected);
             i.append(&self.icon);
             i.append(&self.address);
+            i.append(&self.uuids);
+            i.append(self.class);
+            i.append(self.appearance);
+            i.append(&self.modalias);
+            i.append(self.legacy_pairing);
+            i.append(self.tx_power);
+            i.append(self.battery_percentage);
+            i.append(&self.media_player);
         });
     }
 }
@@ -92,7 +134,7 @@ impl Append for BluetoothDevice {
 impl Arg for BluetoothDevice {
     const ARG_TYPE: arg::ArgType = ArgType::Struct;
     fn signature() -> Signature<'static> {
-        unsafe { Signature::from_slice_unchecked("(onssobbbbbss)\0") }
+        unsafe { Signature::from_slice_unchecked("(onssobbbbbssasuqsbnno)\0") }
     }
 }
 
@@ -101,7 +143,7 @@ impl RefArg for BluetoothDevice {
         ArgType::Struct
     }
     fn signature(&self) -> Signature<'static> {
-        unsafe { Signature::from_slice_unchecked("(onssobbbbbss)\0") }
+        unsafe { Signature::from_slice_unchecked("(onssobbbbbssasuqsbnno)\0") }
     }
     fn append(&self, i: &mut IterAppend) {
         self.append_by_ref(i);
@@ -126,6 +168,112 @@ impl RefArg for BluetoothDevice {
     }
 }
 
+impl BluetoothDevice {
+    /// Builds the device from the interfaces of a BlueZ object, as returned by
+    /// `GetManagedObjects` or sent with `InterfacesAdded`.
+    /// Returns None if the object does not implement [`BLUEZ_DEVICE_INTERFACE`].
+    pub fn from_interfaces(
+        path: Path<'static>,
+        interfaces: &HashMap<String, PropMap>,
+    ) -> Option<Self> {
+        interfaces.get(BLUEZ_DEVICE_INTERFACE)?;
+        let mut device = BluetoothDevice {
+            path,
+            ..Default::default()
+        };
+        for (interface, properties) in interfaces {
+            device.apply_properties(interface, properties);
+        }
+        Some(device)
+    }
+
+    /// Updates the fields provided by `interface`, properties not in `properties` are kept.
+    /// Returns false if the interface is not used for devices.
+    pub fn apply_properties(&mut self, interface: &str, properties: &PropMap) -> bool {
+        match interface {
+            BLUEZ_DEVICE_INTERFACE => {
+                for (key, value) in properties {
+                    let value = &value.0;
+                    match key.as_str() {
+                        "Address" => set_string(&mut self.address, value),
+                        "Name" => set_string(&mut self.name, value),
+                        "Alias" => set_string(&mut self.alias, value),
+                        "Icon" => set_string(&mut self.icon, value),
+                        "Modalias" => set_string(&mut self.modalias, value),
+                        "Adapter" => set_path(&mut self.adapter, value),
+                        "Paired" => set_bool(&mut self.paired, value),
+                        "Bonded" => set_bool(&mut self.bonded, value),
+                        "Trusted" => set_bool(&mut self.trusted, value),
+                        "Blocked" => set_bool(&mut self.blocked, value),
+                        "Connected" => set_bool(&mut self.connected, value),
+                        "LegacyPairing" => set_bool(&mut self.legacy_pairing, value),
+                        "RSSI" => set_number(&mut self.rssi, value),
+                        "TxPower" => set_number(&mut self.tx_power, value),
+                        "Class" => set_number(&mut self.class, value),
+                        "Appearance" => set_number(&mut self.appearance, value),
+                        "UUIDs" => set_strings(&mut self.uuids, value),
+                        _ => (),
+                    }
+                }
+            }
+            BLUEZ_BATTERY_INTERFACE => {
+                if let Some(value) = properties.get("Percentage") {
+                    set_number(&mut self.battery_percentage, &value.0);
+                }
+            }
+            BLUEZ_MEDIA_CONTROL_INTERFACE => {
+                if let Some(value) = properties.get("Player") {
+                    set_path(&mut self.media_player, &value.0);
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Applies a `PropertiesChanged` signal of the device object, invalidated properties are
+    /// reset to their defaults.
+    /// Returns false if the signal is not for an interface used for devices.
+    pub fn apply_properties_changed(&mut self, event: &PropertiesChanged) -> bool {
+        if !self.apply_properties(&event.interface, &event.map) {
+            return false;
+        }
+        let default = BluetoothDevice::default();
+        for property in event.invalid.iter() {
+            match (event.interface.as_str(), property.as_str()) {
+                (BLUEZ_DEVICE_INTERFACE, "Name") => self.name.clear(),
+                (BLUEZ_DEVICE_INTERFACE, "Icon") => self.icon.clear(),
+                (BLUEZ_DEVICE_INTERFACE, "Modalias") => self.modalias.clear(),
+                (BLUEZ_DEVICE_INTERFACE, "RSSI") => self.rssi = default.rssi,
+                (BLUEZ_DEVICE_INTERFACE, "TxPower") => self.tx_power = default.tx_power,
+                (BLUEZ_DEVICE_INTERFACE, "Class") => self.class = default.class,
+                (BLUEZ_DEVICE_INTERFACE, "Appearance") => self.appearance = default.appearance,
+                (BLUEZ_DEVICE_INTERFACE, "UUIDs") => self.uuids.clear(),
+                (BLUEZ_BATTERY_INTERFACE, "Percentage") => {
+                    self.battery_percentage = default.battery_percentage
+                }
+                (BLUEZ_MEDIA_CONTROL_INTERFACE, "Player") => {
+                    self.media_player = default.media_player.clone()
+                }
+                _ => (),
+            }
+        }
+        true
+    }
+
+    /// Resets the fields of interfaces sent with `InterfacesRemoved`, e.g. when the battery
+    /// service disappears on disconnect.
+    pub fn remove_interfaces(&mut self, interfaces: &[String]) {
+        for interface in interfaces {
+            match interface.as_str() {
+                BLUEZ_BATTERY_INTERFACE => self.battery_percentage = -1,
+                BLUEZ_MEDIA_CONTROL_INTERFACE => self.media_player = Path::default(),
+                _ => (),
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct BluetoothAdapter {
     pub path: Path<'static>,
@@ -133,6 +281,15 @@ pub struct BluetoothAdapter {
     pub powered: bool,
     pub discoverable: bool,
     pub pairable: bool,
+    pub address: String,
+    /// Name of the controller, e.g. the hostname, while alias is the user chosen name.
+    pub name: String,
+    pub discovering: bool,
+    /// Seconds until the adapter stops being discoverable, 0 for no timeout.
+    pub discoverable_timeout: u32,
+    /// Seconds until the adapter stops being pairable, 0 for no timeout.
+    pub pairable_timeout: u32,
+    pub uuids: Vec<String>,
 }
 
 unsafe impl Send for BluetoothAdapter {}
@@ -140,14 +297,43 @@ unsafe impl Sync for BluetoothAdapter {}
 
 impl<'a> Get<'a> for BluetoothAdapter {
     fn get(i: &mut arg::Iter<'a>) -> Option<Self> {
-        let (path, alias, powered, discoverable, pairable) =
-            <(Path<'static>, String, bool, bool, bool)>::get(i)?;
+        let (
+            path,
+            alias,
+            powered,
+            discoverable,
+            pairable,
+            address,
+            name,
+            discovering,
+            discoverable_timeout,
+            pairable_timeout,
+            uuids,
+        ) = <(
+            Path<'static>,
+            String,
+            bool,
+            bool,
+            bool,
+            String,
+            String,
+            bool,
+            u32,
+            u32,
+            Vec<String>,
+        )>::get(i)?;
         Some(BluetoothAdapter {
             path,
             alias,
             powered,
             discoverable,
             pairable,
+            address,
+            name,
+            discovering,
+            discoverable_timeout,
+            pairable_timeout,
+            uuids,
         })
     }
 }
@@ -160,6 +346,12 @@ impl Append for BluetoothAdapter {
             i.append(self.powered);
             i.append(self.discoverable);
             i.append(self.pairable);
+            i.append(&self.address);
+            i.append(&self.name);
+            i.append(self.discovering);
+            i.append(self.discoverable_timeout);
+            i.append(self.pairable_timeout);
+            i.append(&self.uuids);
         });
     }
 }
@@ -167,7 +359,7 @@ impl Append for BluetoothAdapter {
 impl Arg for BluetoothAdapter {
     const ARG_TYPE: arg::ArgType = ArgType::Struct;
     fn signature() -> Signature<'static> {
-        unsafe { Signature::from_slice_unchecked("(osbbb)\0") }
+        unsafe { Signature::from_slice_unchecked("(osbbbssbuuas)\0") }
     }
 }
 
@@ -176,7 +368,7 @@ impl RefArg for BluetoothAdapter {
         ArgType::Struct
     }
     fn signature(&self) -> Signature<'static> {
-        unsafe { Signature::from_slice_unchecked("(osbbb)\0") }
+        unsafe { Signature::from_slice_unchecked("(osbbbssbuuas)\0") }
     }
     fn append(&self, i: &mut IterAppend) {
         self.append_by_ref(i);
@@ -200,3 +392,396 @@ impl RefArg for BluetoothAdapter {
         Box::new(self.clone())
     }
 }
+
+impl BluetoothAdapter {
+    /// Builds the adapter from the interfaces of a BlueZ object.
+    /// Returns None if the object does not implement [`BLUEZ_ADAPTER_INTERFACE`].
+    pub fn from_interfaces(
+        path: Path<'static>,
+        interfaces: &HashMap<String, PropMap>,
+    ) -> Option<Self> {
+        let properties = interfaces.get(BLUEZ_ADAPTER_INTERFACE)?;
+        let mut adapter = BluetoothAdapter {
+            path,
+            ..Default::default()
+        };
+        adapter.apply_properties(BLUEZ_ADAPTER_INTERFACE, properties);
+        Some(adapter)
+    }
+
+    /// Updates the fields provided by `interface`, properties not in `properties` are kept.
+    /// Returns false if the interface is not [`BLUEZ_ADAPTER_INTERFACE`].
+    pub fn apply_properties(&mut self, interface: &str, properties: &PropMap) -> bool {
+        if interface != BLUEZ_ADAPTER_INTERFACE {
+            return false;
+        }
+        for (key, value) in properties {
+            let value = &value.0;
+            match key.as_str() {
+                "Address" => set_string(&mut self.address, value),
+                "Name" => set_string(&mut self.name, value),
+                "Alias" => set_string(&mut self.alias, value),
+                "Powered" => set_bool(&mut self.powered, value),
+                "Discoverable" => set_bool(&mut self.discoverable, value),
+                "Pairable" => set_bool(&mut self.pairable, value),
+                "Discovering" => set_bool(&mut self.discovering, value),
+                "DiscoverableTimeout" => set_number(&mut self.discoverable_timeout, value),
+                "PairableTimeout" => set_number(&mut self.pairable_timeout, value),
+                "UUIDs" => set_strings(&mut self.uuids, value),
+                _ => (),
+            }
+        }
+        true
+    }
+
+    pub fn apply_properties_changed(&mut self, event: &PropertiesChanged) -> bool {
+        self.apply_properties(&event.interface, &event.map)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DiscoveryTransport {
+    /// Interleaved scan for classic and low energy devices.
+    #[default]
+    Auto,
+    /// Classic devices only.
+    BrEdr,
+    /// Low energy devices only.
+    Le,
+}
+
+impl Display for DiscoveryTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DiscoveryTransport::Auto => f.write_str("auto"),
+            DiscoveryTransport::BrEdr => f.write_str("bredr"),
+            DiscoveryTransport::Le => f.write_str("le"),
+        }
+    }
+}
+
+impl Enum for DiscoveryTransport {
+    fn from_i32(num: i32) -> Self {
+        match num {
+            1 => DiscoveryTransport::BrEdr,
+            2 => DiscoveryTransport::Le,
+            _ => DiscoveryTransport::Auto,
+        }
+    }
+
+    fn to_i32(&self) -> i32 {
+        match self {
+            DiscoveryTransport::Auto => 0,
+            DiscoveryTransport::BrEdr => 1,
+            DiscoveryTransport::Le => 2,
+        }
+    }
+}
+
+/// Signal threshold of a [`DiscoveryFilter`], BlueZ does not accept RSSI and pathloss together.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DiscoveryRange {
+    #[default]
+    Any,
+    /// Only report devices with a stronger signal in dBm.
+    Rssi(i16),
+    /// Only report devices with a smaller pathloss in dB.
+    Pathloss(u16),
+}
+
+/// Filter for the devices reported during discovery, see `Adapter1.SetDiscoveryFilter`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveryFilter {
+    /// Only report devices advertising one of these services, empty for all devices.
+    pub uuids: Vec<String>,
+    pub range: DiscoveryRange,
+    pub transport: DiscoveryTransport,
+    /// Report every advertisement instead of only changed ones.
+    pub duplicate_data: bool,
+    /// Only report devices that are in discoverable mode.
+    pub discoverable: bool,
+}
+
+impl Default for DiscoveryFilter {
+    fn default() -> Self {
+        Self {
+            uuids: Vec::new(),
+            range: DiscoveryRange::Any,
+            transport: DiscoveryTransport::Auto,
+            duplicate_data: true,
+            discoverable: false,
+        }
+    }
+}
+
+impl DiscoveryFilter {
+    /// Sets the filter for the discovery of this client on `adapter`, an empty filter resets
+    /// it.
+    /// BlueZ stores the filter per D-Bus client and drops it when the client disconnects,
+    /// `conn` therefore has to be the connection that calls `StartDiscovery`.
+    pub fn apply(&self, conn: &Connection, adapter: Path<'static>) -> Result<(), dbus::Error> {
+        let proxy = conn.with_proxy("org.bluez", adapter, Duration::from_millis(1000));
+        proxy.method_call::<(), _, _, _>(
+            BLUEZ_ADAPTER_INTERFACE,
+            "SetDiscoveryFilter",
+            (self.to_propmap(),),
+        )
+    }
+}
+
+impl PropMapConvert for DiscoveryFilter {
+    fn from_propmap(map: &PropMap) -> Self {
+        let mut filter = DiscoveryFilter::default();
+        for (key, value) in map {
+            let value = &value.0;
+            match key.as_str() {
+                "UUIDs" => set_strings(&mut filter.uuids, value),
+                "RSSI" => {
+                    if let Some(rssi) = value.as_i64().and_then(|x| i16::try_from(x).ok()) {
+                        filter.range = DiscoveryRange::Rssi(rssi);
+                    }
+                }
+                // the RSSI takes precedence if a map contains both
+                "Pathloss" if filter.range == DiscoveryRange::Any => {
+                    if let Some(pathloss) = value.as_i64().and_then(|x| u16::try_from(x).ok()) {
+                        filter.range = DiscoveryRange::Pathloss(pathloss);
+                    }
+                }
+                "Transport" => {
+                    filter.transport = match value.as_str() {
+                        Some("bredr") => DiscoveryTransport::BrEdr,
+                        Some("le") => DiscoveryTransport::Le,
+                        _ => DiscoveryTransport::Auto,
+                    }
+                }
+                "DuplicateData" => set_bool(&mut filter.duplicate_data, value),
+                "Discoverable" => set_bool(&mut filter.discoverable, value),
+                _ => (),
+            }
+        }
+        filter
+    }
+
+    /// Only contains the entries that differ from the BlueZ defaults.
+    fn to_propmap(&self) -> PropMap {
+        let mut map = PropMap::new();
+        if !self.uuids.is_empty() {
+            map.insert("UUIDs".into(), Variant(Box::new(self.uuids.clone())));
+        }
+        match self.range {
+            DiscoveryRange::Any => (),
+            DiscoveryRange::Rssi(rssi) => {
+                map.insert("RSSI".into(), Variant(Box::new(rssi)));
+            }
+            DiscoveryRange::Pathloss(pathloss) => {
+                map.insert("Pathloss".into(), Variant(Box::new(pathloss)));
+            }
+        }
+        if self.transport != DiscoveryTransport::Auto {
+            map.insert(
+                "Transport".into(),
+                Variant(Box::new(self.transport.to_string())),
+            );
+        }
+        if !self.duplicate_data {
+            map.insert("DuplicateData".into(), Variant(Box::new(false)));
+        }
+        if self.discoverable {
+            map.insert("Discoverable".into(), Variant(Box::new(true)));
+        }
+        map
+    }
+}
+
+fn set_string(field: &mut String, value: &dyn RefArg) {
+    if let Some(value) = value.as_str() {
+        *field = String::from(value);
+    }
+}
+
+fn set_path(field: &mut Path<'static>, value: &dyn RefArg) {
+    if let Some(Ok(value)) = value.as_str().map(|value| Path::new(String::from(value))) {
+        *field = value;
+    }
+}
+
+fn set_bool(field: &mut bool, value: &dyn RefArg) {
+    if let Some(value) = value.as_u64() {
+        *field = value != 0;
+    }
+}
+
+/// Sets integer properties, values outside of the range of the field are ignored.
+fn set_number<T: TryFrom<i64>>(field: &mut T, value: &dyn RefArg) {
+    if let Some(value) = value.as_i64().and_then(|value| T::try_from(value).ok()) {
+        *field = value;
+    }
+}
+
+fn set_strings(field: &mut Vec<String>, value: &dyn RefArg) {
+    if let Some(values) = value.as_iter() {
+        *field = values
+            .filter_map(|value| value.as_str().map(String::from))
+            .collect();
+    }
+}
+
+#[test]
+fn bluetooth_device_dbus_test() {
+    let device = BluetoothDevice {
+        path: Path::from("/org/bluez/hci0/dev_00_11_22_33_44_55"),
+        adapter: Path::from("/org/bluez/hci0"),
+        name: String::from("Headset"),
+        address: String::from("00:11:22:33:44:55"),
+        uuids: vec![String::from("0000110b-0000-1000-8000-00805f9b34fb")],
+        class: 0x240404,
+        modalias: String::from("usb:v1234p5678d0100"),
+        tx_power: -4,
+        battery_percentage: 80,
+        ..Default::default()
+    };
+    let message = dbus::Message::new_signal("/test", "org.test", "Test")
+        .unwrap()
+        .append1(&device);
+    assert_eq!(
+        message.iter_init().signature().to_string(),
+        "(onssobbbbbssasuqsbnno)"
+    );
+    let received: BluetoothDevice = message.read1().unwrap();
+    assert_eq!(received.path, device.path);
+    assert_eq!(received.uuids, device.uuids);
+    assert_eq!(received.class, device.class);
+    assert_eq!(received.modalias, device.modalias);
+    assert_eq!(received.tx_power, -4);
+    assert_eq!(received.battery_percentage, 80);
+    assert_eq!(BluetoothDevice::default().battery_percentage, -1);
+}
+
+#[test]
+fn bluetooth_adapter_dbus_test() {
+    let adapter = BluetoothAdapter {
+        path: Path::from("/org/bluez/hci1"),
+        alias: String::from("Dongle"),
+        powered: true,
+        address: String::from("AA:BB:CC:DD:EE:FF"),
+        name: String::from("workstation #2"),
+        discovering: true,
+        discoverable_timeout: 180,
+        uuids: vec![String::from("0000110e-0000-1000-8000-00805f9b34fb")],
+        ..Default::default()
+    };
+    let message = dbus::Message::new_signal("/test", "org.test", "Test")
+        .unwrap()
+        .append1(&adapter);
+    assert_eq!(
+        message.iter_init().signature().to_string(),
+        "(osbbbssbuuas)"
+    );
+    let received: BluetoothAdapter = message.read1().unwrap();
+    assert_eq!(received.path, adapter.path);
+    assert_eq!(received.address, adapter.address);
+    assert_eq!(received.name, adapter.name);
+    assert!(received.discovering);
+    assert_eq!(received.discoverable_timeout, 180);
+    assert_eq!(received.uuids, adapter.uuids);
+}
+
+#[test]
+fn bluetooth_interfaces_test() {
+    use dbus::arg::Variant;
+
+    let mut device_properties = PropMap::new();
+    device_properties.insert(
+        String::from("Alias"),
+        Variant(Box::new(String::from("Headset"))),
+    );
+    device_properties.insert(
+        String::from("Adapter"),
+        Variant(Box::new(Path::from("/org/bluez/hci0"))),
+    );
+    device_properties.insert(String::from("Connected"), Variant(Box::new(true)));
+    device_properties.insert(String::from("RSSI"), Variant(Box::new(-60i16)));
+    device_properties.insert(String::from("Class"), Variant(Box::new(0x240404u32)));
+    device_properties.insert(
+        String::from("UUIDs"),
+        Variant(Box::new(vec![String::from(
+            "0000110b-0000-1000-8000-00805f9b34fb",
+        )])),
+    );
+    let mut battery_properties = PropMap::new();
+    battery_properties.insert(String::from("Percentage"), Variant(Box::new(70u8)));
+    let mut interfaces = HashMap::new();
+    interfaces.insert(String::from(BLUEZ_DEVICE_INTERFACE), device_properties);
+    interfaces.insert(String::from(BLUEZ_BATTERY_INTERFACE), battery_properties);
+
+    // send the interfaces over D-Bus to get the values as BlueZ provides them
+    let message = dbus::Message::new_signal("/test", "org.test", "Test")
+        .unwrap()
+        .append1(&interfaces);
+    let interfaces: HashMap<String, PropMap> = message.read1().unwrap();
+    let path = Path::from("/org/bluez/hci0/dev_00_11_22_33_44_55");
+    let mut device = BluetoothDevice::from_interfaces(path.clone(), &interfaces).unwrap();
+    assert_eq!(device.path, path);
+    assert_eq!(device.alias, "Headset");
+    assert_eq!(device.adapter, Path::from("/org/bluez/hci0"));
+    assert!(device.connected);
+    assert_eq!(device.rssi, -60);
+    assert_eq!(device.class, 0x240404);
+    assert_eq!(device.uuids.len(), 1);
+    assert_eq!(device.battery_percentage, 70);
+    assert!(BluetoothAdapter::from_interfaces(path, &interfaces).is_none());
+
+    let mut changed = PropMap::new();
+    changed.insert(String::from("Connected"), Variant(Box::new(false)));
+    let event = PropertiesChanged {
+        interface: String::from(BLUEZ_DEVICE_INTERFACE),
+        map: changed,
+        invalid: vec![String::from("RSSI")],
+    };
+    assert!(device.apply_properties_changed(&event));
+    assert!(!device.connected);
+    assert_eq!(device.rssi, 0);
+    assert_eq!(device.alias, "Headset");
+    device.remove_interfaces(&[String::from(BLUEZ_BATTERY_INTERFACE)]);
+    assert_eq!(device.battery_percentage, -1);
+
+    let mut adapter_properties = PropMap::new();
+    adapter_properties.insert(String::from("Powered"), Variant(Box::new(true)));
+    adapter_properties.insert(String::from("PairableTimeout"), Variant(Box::new(60u32)));
+    let mut interfaces = HashMap::new();
+    interfaces.insert(String::from(BLUEZ_ADAPTER_INTERFACE), adapter_properties);
+    let adapter =
+        BluetoothAdapter::from_interfaces(Path::from("/org/bluez/hci0"), &interfaces).unwrap();
+    assert!(adapter.powered);
+    assert_eq!(adapter.pairable_timeout, 60);
+}
+
+#[test]
+fn discovery_filter_test() {
+    assert!(DiscoveryFilter::default().to_propmap().is_empty());
+
+    let mut filter = DiscoveryFilter {
+        range: DiscoveryRange::Rssi(-70),
+        transport: DiscoveryTransport::Le,
+        duplicate_data: false,
+        ..Default::default()
+    };
+    let map = filter.to_propmap();
+    assert_eq!(map.len(), 3);
+    let message = dbus::Message::new_signal("/test", "org.test", "Test")
+        .unwrap()
+        .append1(&map);
+    assert_eq!(message.iter_init().signature().to_string(), "a{sv}");
+    let received = DiscoveryFilter::from_propmap(&message.read1().unwrap());
+    assert_eq!(received, filter);
+
+    filter.range = DiscoveryRange::Pathloss(40);
+    let map = filter.to_propmap();
+    assert!(map.contains_key("Pathloss"));
+    assert!(!map.contains_key("RSSI"));
+    let message = dbus::Message::new_signal("/test", "org.test", "Test")
+        .unwrap()
+        .append1(&map);
+    let received = DiscoveryFilter::from_propmap(&message.read1().unwrap());
+    assert_eq!(received, filter);
+}
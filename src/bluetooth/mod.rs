@@ -1,2 +1,3 @@
+pub mod bluetooth_agent;
 pub mod bluetooth_signals;
 pub mod bluetooth_structures;
@@ -4,10 +4,13 @@ use dbus::{
 };
 
 use crate::{
-    audio::audio_structures::{
-        InputStream, OutputStream, Sink, Source, TAudioObject, TAudioStreamObject,
+    audio::{
+        audio_changes::ChangeMask,
+        audio_structures::{
+            InputStream, Module, OutputStream, Sink, Source, TAudioObject, TAudioStreamObject,
+        },
     },
-    bluetooth::bluetooth_structures::BluetoothDevice,
+    bluetooth::bluetooth_structures::{BluetoothAdapter, BluetoothDevice},
     network::network_structures::{AccessPoint, WifiDevice},
     utils::dbus_utils::{AUDIO, BLUETOOTH, WIRELESS},
 };
@@ -106,6 +109,316 @@ impl GetVal<(Path<'static>,)> for BluetoothDeviceRemoved {
     }
 }
 
+#[derive(Debug)]
+pub struct BluetoothAdapterAdded {
+    pub adapter: BluetoothAdapter,
+}
+
+impl arg::AppendAll for BluetoothAdapterAdded {
+    fn append(&self, i: &mut arg::IterAppend) {
+        arg::RefArg::append(&self.adapter, i);
+    }
+}
+
+impl arg::ReadAll for BluetoothAdapterAdded {
+    fn read(i: &mut arg::Iter) -> Result<Self, arg::TypeMismatchError> {
+        Ok(BluetoothAdapterAdded { adapter: i.read()? })
+    }
+}
+
+impl GetVal<(BluetoothAdapter,)> for BluetoothAdapterAdded {
+    fn get_value(&self) -> (BluetoothAdapter,) {
+        (self.adapter.clone(),)
+    }
+}
+
+impl dbus::message::SignalArgs for BluetoothAdapterAdded {
+    const NAME: &'static str = "BluetoothAdapterAdded";
+    const INTERFACE: &'static str = BLUETOOTH;
+}
+
+#[derive(Debug)]
+pub struct BluetoothAdapterChanged {
+    pub adapter: BluetoothAdapter,
+}
+
+impl arg::AppendAll for BluetoothAdapterChanged {
+    fn append(&self, i: &mut arg::IterAppend) {
+        arg::RefArg::append(&self.adapter, i);
+    }
+}
+
+impl arg::ReadAll for BluetoothAdapterChanged {
+    fn read(i: &mut arg::Iter) -> Result<Self, arg::TypeMismatchError> {
+        Ok(BluetoothAdapterChanged { adapter: i.read()? })
+    }
+}
+
+impl GetVal<(BluetoothAdapter,)> for BluetoothAdapterChanged {
+    fn get_value(&self) -> (BluetoothAdapter,) {
+        (self.adapter.clone(),)
+    }
+}
+
+impl dbus::message::SignalArgs for BluetoothAdapterChanged {
+    const NAME: &'static str = "BluetoothAdapterChanged";
+    const INTERFACE: &'static str = BLUETOOTH;
+}
+
+#[derive(Debug)]
+pub struct BluetoothAdapterRemoved {
+    pub adapter: Path<'static>,
+}
+
+impl arg::AppendAll for BluetoothAdapterRemoved {
+    fn append(&self, i: &mut arg::IterAppend) {
+        arg::RefArg::append(&self.adapter, i);
+    }
+}
+
+impl arg::ReadAll for BluetoothAdapterRemoved {
+    fn read(i: &mut arg::Iter) -> Result<Self, arg::TypeMismatchError> {
+        Ok(BluetoothAdapterRemoved { adapter: i.read()? })
+    }
+}
+
+impl dbus::message::SignalArgs for BluetoothAdapterRemoved {
+    const NAME: &'static str = "BluetoothAdapterRemoved";
+    const INTERFACE: &'static str = BLUETOOTH;
+}
+
+impl GetVal<(Path<'static>,)> for BluetoothAdapterRemoved {
+    fn get_value(&self) -> (Path<'static>,) {
+        (self.adapter.clone(),)
+    }
+}
+
+/// Pairing requires a pin code for the device, answer with a pin code reply.
+#[derive(Debug)]
+pub struct BluetoothPinRequested {
+    pub device: Path<'static>,
+}
+
+impl arg::AppendAll for BluetoothPinRequested {
+    fn append(&self, i: &mut arg::IterAppend) {
+        arg::RefArg::append(&self.device, i);
+    }
+}
+
+impl arg::ReadAll for BluetoothPinRequested {
+    fn read(i: &mut arg::Iter) -> Result<Self, arg::TypeMismatchError> {
+        Ok(BluetoothPinRequested { device: i.read()? })
+    }
+}
+
+impl GetVal<(Path<'static>,)> for BluetoothPinRequested {
+    fn get_value(&self) -> (Path<'static>,) {
+        (self.device.clone(),)
+    }
+}
+
+impl dbus::message::SignalArgs for BluetoothPinRequested {
+    const NAME: &'static str = "BluetoothPinRequested";
+    const INTERFACE: &'static str = BLUETOOTH;
+}
+
+/// Pairing requires a numeric passkey for the device, answer with a passkey reply.
+#[derive(Debug)]
+pub struct BluetoothPasskeyRequested {
+    pub device: Path<'static>,
+}
+
+impl arg::AppendAll for BluetoothPasskeyRequested {
+    fn append(&self, i: &mut arg::IterAppend) {
+        arg::RefArg::append(&self.device, i);
+    }
+}
+
+impl arg::ReadAll for BluetoothPasskeyRequested {
+    fn read(i: &mut arg::Iter) -> Result<Self, arg::TypeMismatchError> {
+        Ok(BluetoothPasskeyRequested { device: i.read()? })
+    }
+}
+
+impl GetVal<(Path<'static>,)> for BluetoothPasskeyRequested {
+    fn get_value(&self) -> (Path<'static>,) {
+        (self.device.clone(),)
+    }
+}
+
+impl dbus::message::SignalArgs for BluetoothPasskeyRequested {
+    const NAME: &'static str = "BluetoothPasskeyRequested";
+    const INTERFACE: &'static str = BLUETOOTH;
+}
+
+/// The pin code has to be entered on the device, no reply is needed.
+#[derive(Debug)]
+pub struct BluetoothPinDisplayed {
+    pub device: Path<'static>,
+    pub pin_code: String,
+}
+
+impl arg::AppendAll for BluetoothPinDisplayed {
+    fn append(&self, i: &mut arg::IterAppend) {
+        arg::RefArg::append(&self.device, i);
+        arg::RefArg::append(&self.pin_code, i);
+    }
+}
+
+impl arg::ReadAll for BluetoothPinDisplayed {
+    fn read(i: &mut arg::Iter) -> Result<Self, arg::TypeMismatchError> {
+        Ok(BluetoothPinDisplayed {
+            device: i.read()?,
+            pin_code: i.read()?,
+        })
+    }
+}
+
+impl GetVal<(Path<'static>, String)> for BluetoothPinDisplayed {
+    fn get_value(&self) -> (Path<'static>, String) {
+        (self.device.clone(), self.pin_code.clone())
+    }
+}
+
+impl dbus::message::SignalArgs for BluetoothPinDisplayed {
+    const NAME: &'static str = "BluetoothPinDisplayed";
+    const INTERFACE: &'static str = BLUETOOTH;
+}
+
+/// The passkey has to be entered on the device, `entered` is the number of typed digits.
+#[derive(Debug)]
+pub struct BluetoothPasskeyDisplayed {
+    pub device: Path<'static>,
+    pub passkey: u32,
+    pub entered: u16,
+}
+
+impl arg::AppendAll for BluetoothPasskeyDisplayed {
+    fn append(&self, i: &mut arg::IterAppend) {
+        arg::RefArg::append(&self.device, i);
+        arg::RefArg::append(&self.passkey, i);
+        arg::RefArg::append(&self.entered, i);
+    }
+}
+
+impl arg::ReadAll for BluetoothPasskeyDisplayed {
+    fn read(i: &mut arg::Iter) -> Result<Self, arg::TypeMismatchError> {
+        Ok(BluetoothPasskeyDisplayed {
+            device: i.read()?,
+            passkey: i.read()?,
+            entered: i.read()?,
+        })
+    }
+}
+
+impl GetVal<(Path<'static>, u32, u16)> for BluetoothPasskeyDisplayed {
+    fn get_value(&self) -> (Path<'static>, u32, u16) {
+        (self.device.clone(), self.passkey, self.entered)
+    }
+}
+
+impl dbus::message::SignalArgs for BluetoothPasskeyDisplayed {
+    const NAME: &'static str = "BluetoothPasskeyDisplayed";
+    const INTERFACE: &'static str = BLUETOOTH;
+}
+
+/// The passkey shown on the device has to be confirmed or rejected.
+#[derive(Debug)]
+pub struct BluetoothConfirmationRequested {
+    pub device: Path<'static>,
+    pub passkey: u32,
+}
+
+impl arg::AppendAll for BluetoothConfirmationRequested {
+    fn append(&self, i: &mut arg::IterAppend) {
+        arg::RefArg::append(&self.device, i);
+        arg::RefArg::append(&self.passkey, i);
+    }
+}
+
+impl arg::ReadAll for BluetoothConfirmationRequested {
+    fn read(i: &mut arg::Iter) -> Result<Self, arg::TypeMismatchError> {
+        Ok(BluetoothConfirmationRequested {
+            device: i.read()?,
+            passkey: i.read()?,
+        })
+    }
+}
+
+impl GetVal<(Path<'static>, u32)> for BluetoothConfirmationRequested {
+    fn get_value(&self) -> (Path<'static>, u32) {
+        (self.device.clone(), self.passkey)
+    }
+}
+
+impl dbus::message::SignalArgs for BluetoothConfirmationRequested {
+    const NAME: &'static str = "BluetoothConfirmationRequested";
+    const INTERFACE: &'static str = BLUETOOTH;
+}
+
+/// The device requests pairing or access to the service `uuid`, empty for pairing.
+#[derive(Debug)]
+pub struct BluetoothAuthorizationRequested {
+    pub device: Path<'static>,
+    pub uuid: String,
+}
+
+impl arg::AppendAll for BluetoothAuthorizationRequested {
+    fn append(&self, i: &mut arg::IterAppend) {
+        arg::RefArg::append(&self.device, i);
+        arg::RefArg::append(&self.uuid, i);
+    }
+}
+
+impl arg::ReadAll for BluetoothAuthorizationRequested {
+    fn read(i: &mut arg::Iter) -> Result<Self, arg::TypeMismatchError> {
+        Ok(BluetoothAuthorizationRequested {
+            device: i.read()?,
+            uuid: i.read()?,
+        })
+    }
+}
+
+impl GetVal<(Path<'static>, String)> for BluetoothAuthorizationRequested {
+    fn get_value(&self) -> (Path<'static>, String) {
+        (self.device.clone(), self.uuid.clone())
+    }
+}
+
+impl dbus::message::SignalArgs for BluetoothAuthorizationRequested {
+    const NAME: &'static str = "BluetoothAuthorizationRequested";
+    const INTERFACE: &'static str = BLUETOOTH;
+}
+
+/// The pending pairing request of the device was canceled by BlueZ.
+#[derive(Debug)]
+pub struct BluetoothPairingCanceled {
+    pub device: Path<'static>,
+}
+
+impl arg::AppendAll for BluetoothPairingCanceled {
+    fn append(&self, i: &mut arg::IterAppend) {
+        arg::RefArg::append(&self.device, i);
+    }
+}
+
+impl arg::ReadAll for BluetoothPairingCanceled {
+    fn read(i: &mut arg::Iter) -> Result<Self, arg::TypeMismatchError> {
+        Ok(BluetoothPairingCanceled { device: i.read()? })
+    }
+}
+
+impl GetVal<(Path<'static>,)> for BluetoothPairingCanceled {
+    fn get_value(&self) -> (Path<'static>,) {
+        (self.device.clone(),)
+    }
+}
+
+impl dbus::message::SignalArgs for BluetoothPairingCanceled {
+    const NAME: &'static str = "BluetoothPairingCanceled";
+    const INTERFACE: &'static str = BLUETOOTH;
+}
+
 #[derive(Debug)]
 pub struct AccessPointAdded {
     pub access_point: AccessPoint,
@@ -353,17 +666,22 @@ impl TAudioObjectEvent<Sink> for SinkAdded {
 #[derive(Debug)]
 pub struct SinkChanged {
     pub sink: Sink,
+    pub changes: ChangeMask,
 }
 
 impl arg::AppendAll for SinkChanged {
     fn append(&self, i: &mut arg::IterAppend) {
         self.sink.append_by_ref(i);
+        self.changes.append_by_ref(i);
     }
 }
 
 impl arg::ReadAll for SinkChanged {
     fn read(i: &mut arg::Iter) -> Result<Self, arg::TypeMismatchError> {
-        Ok(SinkChanged { sink: i.read()? })
+        Ok(SinkChanged {
+            sink: i.read()?,
+            changes: i.read()?,
+        })
     }
 }
 
@@ -372,9 +690,9 @@ impl dbus::message::SignalArgs for SinkChanged {
     const INTERFACE: &'static str = AUDIO;
 }
 
-impl GetVal<(Sink,)> for SinkChanged {
-    fn get_value(&self) -> (Sink,) {
-        (self.sink.clone(),)
+impl GetVal<(Sink, ChangeMask)> for SinkChanged {
+    fn get_value(&self) -> (Sink, ChangeMask) {
+        (self.sink.clone(), self.changes)
     }
 }
 
@@ -392,6 +710,44 @@ impl TAudioObjectEvent<Sink> for SinkChanged {
     }
 }
 
+/// Current peak of each channel of a sink or source in the range 0.0..=1.0.
+/// Sink levels are measured on their monitor source, the index still refers to the sink.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LevelMeter {
+    pub index: u32,
+    pub is_source: bool,
+    pub peaks: Vec<f64>,
+}
+
+impl arg::AppendAll for LevelMeter {
+    fn append(&self, i: &mut arg::IterAppend) {
+        self.index.append_by_ref(i);
+        self.is_source.append_by_ref(i);
+        self.peaks.append_by_ref(i);
+    }
+}
+
+impl arg::ReadAll for LevelMeter {
+    fn read(i: &mut arg::Iter) -> Result<Self, arg::TypeMismatchError> {
+        Ok(LevelMeter {
+            index: i.read()?,
+            is_source: i.read()?,
+            peaks: i.read()?,
+        })
+    }
+}
+
+impl dbus::message::SignalArgs for LevelMeter {
+    const NAME: &'static str = "LevelMeter";
+    const INTERFACE: &'static str = AUDIO;
+}
+
+impl GetVal<(u32, bool, Vec<f64>)> for LevelMeter {
+    fn get_value(&self) -> (u32, bool, Vec<f64>) {
+        (self.index, self.is_source, self.peaks.clone())
+    }
+}
+
 #[derive(Debug)]
 pub struct SinkRemoved {
     pub index: u32,
@@ -426,6 +782,34 @@ impl TAudioEventRemoved for SinkRemoved {
     }
 }
 
+#[derive(Debug)]
+pub struct DefaultSinkChanged {
+    pub name: String,
+}
+
+impl arg::AppendAll for DefaultSinkChanged {
+    fn append(&self, i: &mut arg::IterAppend) {
+        self.name.append_by_ref(i);
+    }
+}
+
+impl arg::ReadAll for DefaultSinkChanged {
+    fn read(i: &mut arg::Iter) -> Result<Self, arg::TypeMismatchError> {
+        Ok(DefaultSinkChanged { name: i.read()? })
+    }
+}
+
+impl dbus::message::SignalArgs for DefaultSinkChanged {
+    const NAME: &'static str = "DefaultSinkChanged";
+    const INTERFACE: &'static str = AUDIO;
+}
+
+impl GetVal<(String,)> for DefaultSinkChanged {
+    fn get_value(&self) -> (String,) {
+        (self.name.clone(),)
+    }
+}
+
 #[derive(Debug)]
 pub struct InputStreamAdded {
     pub stream: InputStream,
@@ -471,17 +855,22 @@ impl TAudioStreamEvent<InputStream> for InputStreamAdded {
 #[derive(Debug)]
 pub struct InputStreamChanged {
     pub stream: InputStream,
+    pub changes: ChangeMask,
 }
 
 impl arg::AppendAll for InputStreamChanged {
     fn append(&self, i: &mut arg::IterAppend) {
         self.stream.append_by_ref(i);
+        self.changes.append_by_ref(i);
     }
 }
 
 impl arg::ReadAll for InputStreamChanged {
     fn read(i: &mut arg::Iter) -> Result<Self, arg::TypeMismatchError> {
-        Ok(InputStreamChanged { stream: i.read()? })
+        Ok(InputStreamChanged {
+            stream: i.read()?,
+            changes: i.read()?,
+        })
     }
 }
 
@@ -583,17 +972,22 @@ impl TAudioObjectEvent<Source> for SourceAdded {
 #[derive(Debug)]
 pub struct SourceChanged {
     pub source: Source,
+    pub changes: ChangeMask,
 }
 
 impl arg::AppendAll for SourceChanged {
     fn append(&self, i: &mut arg::IterAppend) {
         self.source.append_by_ref(i);
+        self.changes.append_by_ref(i);
     }
 }
 
 impl arg::ReadAll for SourceChanged {
     fn read(i: &mut arg::Iter) -> Result<Self, arg::TypeMismatchError> {
-        Ok(SourceChanged { source: i.read()? })
+        Ok(SourceChanged {
+            source: i.read()?,
+            changes: i.read()?,
+        })
     }
 }
 
@@ -602,9 +996,9 @@ impl dbus::message::SignalArgs for SourceChanged {
     const INTERFACE: &'static str = AUDIO;
 }
 
-impl GetVal<(Source,)> for SourceChanged {
-    fn get_value(&self) -> (Source,) {
-        (self.source.clone(),)
+impl GetVal<(Source, ChangeMask)> for SourceChanged {
+    fn get_value(&self) -> (Source, ChangeMask) {
+        (self.source.clone(), self.changes)
     }
 }
 
@@ -656,6 +1050,34 @@ impl TAudioEventRemoved for SourceRemoved {
     }
 }
 
+#[derive(Debug)]
+pub struct DefaultSourceChanged {
+    pub name: String,
+}
+
+impl arg::AppendAll for DefaultSourceChanged {
+    fn append(&self, i: &mut arg::IterAppend) {
+        self.name.append_by_ref(i);
+    }
+}
+
+impl arg::ReadAll for DefaultSourceChanged {
+    fn read(i: &mut arg::Iter) -> Result<Self, arg::TypeMismatchError> {
+        Ok(DefaultSourceChanged { name: i.read()? })
+    }
+}
+
+impl dbus::message::SignalArgs for DefaultSourceChanged {
+    const NAME: &'static str = "DefaultSourceChanged";
+    const INTERFACE: &'static str = AUDIO;
+}
+
+impl GetVal<(String,)> for DefaultSourceChanged {
+    fn get_value(&self) -> (String,) {
+        (self.name.clone(),)
+    }
+}
+
 #[derive(Debug)]
 pub struct OutputStreamAdded {
     pub stream: OutputStream,
@@ -701,17 +1123,22 @@ impl TAudioStreamEvent<OutputStream> for OutputStreamAdded {
 #[derive(Debug)]
 pub struct OutputStreamChanged {
     pub stream: OutputStream,
+    pub changes: ChangeMask,
 }
 
 impl arg::AppendAll for OutputStreamChanged {
     fn append(&self, i: &mut arg::IterAppend) {
         self.stream.append_by_ref(i);
+        self.changes.append_by_ref(i);
     }
 }
 
 impl arg::ReadAll for OutputStreamChanged {
     fn read(i: &mut arg::Iter) -> Result<Self, arg::TypeMismatchError> {
-        Ok(OutputStreamChanged { stream: i.read()? })
+        Ok(OutputStreamChanged {
+            stream: i.read()?,
+            changes: i.read()?,
+        })
     }
 }
 
@@ -768,6 +1195,68 @@ impl TAudioEventRemoved for OutputStreamRemoved {
     }
 }
 
+#[derive(Debug)]
+pub struct ModuleAdded {
+    pub module: Module,
+}
+
+impl arg::AppendAll for ModuleAdded {
+    fn append(&self, i: &mut arg::IterAppend) {
+        self.module.append_by_ref(i);
+    }
+}
+
+impl arg::ReadAll for ModuleAdded {
+    fn read(i: &mut arg::Iter) -> Result<Self, arg::TypeMismatchError> {
+        Ok(ModuleAdded { module: i.read()? })
+    }
+}
+
+impl dbus::message::SignalArgs for ModuleAdded {
+    const NAME: &'static str = "ModuleAdded";
+    const INTERFACE: &'static str = AUDIO;
+}
+
+impl GetVal<(Module,)> for ModuleAdded {
+    fn get_value(&self) -> (Module,) {
+        (self.module.clone(),)
+    }
+}
+
+#[derive(Debug)]
+pub struct ModuleRemoved {
+    pub index: u32,
+}
+
+impl arg::AppendAll for ModuleRemoved {
+    fn append(&self, i: &mut arg::IterAppend) {
+        self.index.append_by_ref(i);
+    }
+}
+
+impl arg::ReadAll for ModuleRemoved {
+    fn read(i: &mut arg::Iter) -> Result<Self, arg::TypeMismatchError> {
+        Ok(ModuleRemoved { index: i.read()? })
+    }
+}
+
+impl dbus::message::SignalArgs for ModuleRemoved {
+    const NAME: &'static str = "ModuleRemoved";
+    const INTERFACE: &'static str = AUDIO;
+}
+
+impl GetVal<(u32,)> for ModuleRemoved {
+    fn get_value(&self) -> (u32,) {
+        (self.index,)
+    }
+}
+
+impl TAudioEventRemoved for ModuleRemoved {
+    fn index(&self) -> u32 {
+        self.index
+    }
+}
+
 #[derive(Debug)]
 pub struct PropertiesChanged {
     pub interface: String,
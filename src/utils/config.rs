@@ -1,4 +1,4 @@
-use std::{fs, io::Read};
+use std::{fs, io::ErrorKind, io::Read};
 
 use once_cell::sync::Lazy;
 use toml::Table;
@@ -60,3 +60,86 @@ pub fn get_config_value<T, F: Fn(&toml::value::Value) -> T>(
     }
     false
 }
+
+/// Reads the config file for modification, a missing file results in an empty table.
+/// Returns None if the file exists but can not be read or parsed, writing the config would
+/// then discard the entries of the user.
+fn read_config() -> Option<Table> {
+    let config_string = match fs::read_to_string(unsafe { CONFIG_STRING.as_str() }) {
+        Ok(config_string) => config_string,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Some(Table::new()),
+        Err(_error) => {
+            ERROR!(
+                format!("Could not read config file: {}", _error),
+                ErrorLevel::Recoverable
+            );
+            return None;
+        }
+    };
+    match config_string.parse::<Table>() {
+        Ok(config) => Some(config),
+        Err(_error) => {
+            ERROR!(
+                format!("Config has errors: {}", _error),
+                ErrorLevel::Recoverable
+            );
+            None
+        }
+    }
+}
+
+/// Writes `value` to `entry` in `category` of the config file while keeping all other entries.
+/// Nothing is written if the existing config can not be read.
+/// Note that [`CONFIG`] is only parsed once, use [`parse_config`] to read the updated file.
+pub fn write_config_value(category: &str, entry: &str, value: toml::Value) -> bool {
+    let mut values = Table::new();
+    values.insert(entry.to_string(), value);
+    write_config_values(category, values)
+}
+
+/// Writes all `values` to `category` with a single write, see [`write_config_value`].
+pub fn write_config_values(category: &str, values: Table) -> bool {
+    let Some(mut config) = read_config() else {
+        return false;
+    };
+    let category_table = config
+        .entry(category)
+        .or_insert_with(|| toml::Value::Table(Table::new()));
+    let toml::Value::Table(category_table) = category_table else {
+        ERROR!(
+            format!("Config category {} is not a table", category),
+            ErrorLevel::Recoverable
+        );
+        return false;
+    };
+    category_table.extend(values);
+    write_config(&config)
+}
+
+/// Removes `entry` from `category` of the config file, returns false if it did not exist.
+pub fn remove_config_value(category: &str, entry: &str) -> bool {
+    let Some(mut config) = read_config() else {
+        return false;
+    };
+    let Some(toml::Value::Table(category_table)) = config.get_mut(category) else {
+        return false;
+    };
+    if category_table.remove(entry).is_none() {
+        return false;
+    }
+    write_config(&config)
+}
+
+fn write_config(config: &Table) -> bool {
+    unsafe {
+        let res = fs::write(CONFIG_STRING.as_str(), config.to_string());
+        if let Err(_error) = res {
+            ERROR!(
+                format!("Could not write config file: {}", _error),
+                ErrorLevel::Recoverable
+            );
+            return false;
+        }
+    }
+    true
+}